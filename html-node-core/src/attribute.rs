@@ -0,0 +1,350 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::{escape, IntoAttributeValue};
+
+/// A list of classes for a `class` attribute, without duplicates.
+///
+/// Classes can be added from strings (which can have several
+/// whitespace-separated classes), [`Option`]s (which add nothing when
+/// [`None`]) and `(classes, bool)` pairs (which add nothing when `false`).
+/// The `html!` macro builds one from `class=[...]`, and so does the
+/// `classes!` macro.
+///
+/// An empty list leaves the `class` attribute out.
+///
+/// ```rust
+/// use html_node_core::Classes;
+///
+/// let is_active = true;
+/// let size = Some("btn-large");
+///
+/// let classes = Classes::new()
+///     .with("btn")
+///     .with(("active", is_active))
+///     .with(("disabled", false))
+///     .with(size)
+///     .with("btn active");
+///
+/// assert_eq!(classes.to_string(), "btn active btn-large");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Classes {
+    names: Vec<String>,
+}
+
+impl Classes {
+    /// An empty list of classes.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { names: Vec::new() }
+    }
+
+    /// Add classes, unless the list already has them.
+    #[must_use]
+    pub fn with(mut self, classes: impl Into<Self>) -> Self {
+        self.add(classes);
+        self
+    }
+
+    /// Add classes, unless the list already has them.
+    pub fn add(&mut self, classes: impl Into<Self>) {
+        for name in classes.into().names {
+            if !self.contains(&name) {
+                self.names.push(name);
+            }
+        }
+    }
+
+    /// Whether the list has the given class.
+    #[must_use]
+    pub fn contains(&self, class: &str) -> bool {
+        self.names.iter().any(|name| name == class)
+    }
+
+    /// Whether the list has no classes.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// The classes, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+}
+
+impl Display for Classes {
+    /// Format the classes separated by spaces.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, name) in self.names.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+
+            f.write_str(name)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<&str> for Classes {
+    /// Split whitespace-separated classes.
+    fn from(classes: &str) -> Self {
+        let mut names = Vec::<String>::new();
+
+        for class in classes.split_ascii_whitespace() {
+            if !names.iter().any(|name| name == class) {
+                names.push(class.into());
+            }
+        }
+
+        Self { names }
+    }
+}
+
+impl From<String> for Classes {
+    /// Split whitespace-separated classes.
+    fn from(classes: String) -> Self {
+        Self::from(classes.as_str())
+    }
+}
+
+impl From<&String> for Classes {
+    /// Split whitespace-separated classes.
+    fn from(classes: &String) -> Self {
+        Self::from(classes.as_str())
+    }
+}
+
+impl From<Cow<'_, str>> for Classes {
+    /// Split whitespace-separated classes.
+    fn from(classes: Cow<'_, str>) -> Self {
+        Self::from(&*classes)
+    }
+}
+
+impl<T: Into<Self>> From<Option<T>> for Classes {
+    /// The classes if there are some, or none otherwise.
+    fn from(classes: Option<T>) -> Self {
+        classes.map_or_else(Self::new, Into::into)
+    }
+}
+
+impl<T: Into<Self>> From<(T, bool)> for Classes {
+    /// The classes if the condition is `true`, or none otherwise.
+    fn from((classes, condition): (T, bool)) -> Self {
+        if condition {
+            classes.into()
+        } else {
+            Self::new()
+        }
+    }
+}
+
+impl<T: Into<Self>> Extend<T> for Classes {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for classes in iter {
+            self.add(classes);
+        }
+    }
+}
+
+impl<T: Into<Self>> FromIterator<T> for Classes {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut classes = Self::new();
+        classes.extend(iter);
+        classes
+    }
+}
+
+impl From<Classes> for String {
+    fn from(classes: Classes) -> Self {
+        classes.names.join(" ")
+    }
+}
+
+impl IntoAttributeValue for Classes {
+    /// The classes, or [`None`] if there are none.
+    fn into_attribute_value(self) -> Option<Option<String>> {
+        (!self.is_empty()).then(|| Some(self.into()))
+    }
+}
+
+/// A list of CSS declarations for a `style` attribute.
+///
+/// Values can be strings, numbers, [`Option`]s (which leave the declaration
+/// out when [`None`]) and `(bool, value)` pairs (which leave the declaration
+/// out when `false`). Setting a property which is already set replaces its
+/// value. Properties and values are escaped with
+/// [`escape::css_declaration`].
+///
+/// The `html!` macro builds one from `style={ {"property": value, ...} }`.
+///
+/// An empty list leaves the `style` attribute out.
+///
+/// ```rust
+/// use html_node_core::Style;
+///
+/// let hidden = true;
+///
+/// let style = Style::new()
+///     .with("color", "red")
+///     .with("display", (hidden, "none"))
+///     .with("margin", None::<&str>)
+///     .with("opacity", 0.5)
+///     .with("color", "blue; position: fixed");
+///
+/// assert_eq!(
+///     style.to_string(),
+///     r"color: blue\3B  position: fixed; display: none; opacity: 0.5",
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Style {
+    declarations: Vec<(String, String)>,
+}
+
+impl Style {
+    /// An empty list of declarations.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            declarations: Vec::new(),
+        }
+    }
+
+    /// Set a property, replacing its value if it's already set.
+    #[must_use]
+    pub fn with(mut self, property: impl Into<String>, value: impl IntoStyleValue) -> Self {
+        self.set(property, value);
+        self
+    }
+
+    /// Set a property, replacing its value if it's already set.
+    pub fn set(&mut self, property: impl Into<String>, value: impl IntoStyleValue) {
+        let Some(value) = value.into_style_value() else {
+            return;
+        };
+
+        let property = property.into();
+
+        match self
+            .declarations
+            .iter_mut()
+            .find(|(other, _)| other.eq_ignore_ascii_case(&property))
+        {
+            Some((_, existing)) => *existing = value,
+            None => self.declarations.push((property, value)),
+        }
+    }
+
+    /// The value of a property, if it's set.
+    #[must_use]
+    pub fn get(&self, property: &str) -> Option<&str> {
+        self.declarations
+            .iter()
+            .find(|(other, _)| other.eq_ignore_ascii_case(property))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether the list has no declarations.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.declarations.is_empty()
+    }
+
+    /// The properties and their values, in the order they were first set.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.declarations
+            .iter()
+            .map(|(property, value)| (property.as_str(), value.as_str()))
+    }
+}
+
+impl Display for Style {
+    /// Format the declarations like `color: red; display: none`, escaping
+    /// their properties and values.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, (property, value)) in self.declarations.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+
+            write!(
+                f,
+                "{}: {}",
+                escape::css_declaration(property),
+                escape::css_declaration(value),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<K: Into<String>, V: IntoStyleValue> Extend<(K, V)> for Style {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (property, value) in iter {
+            self.set(property, value);
+        }
+    }
+}
+
+impl<K: Into<String>, V: IntoStyleValue> FromIterator<(K, V)> for Style {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut style = Self::new();
+        style.extend(iter);
+        style
+    }
+}
+
+impl From<Style> for String {
+    fn from(style: Style) -> Self {
+        style.to_string()
+    }
+}
+
+impl IntoAttributeValue for Style {
+    /// The declarations, or [`None`] if there are none.
+    fn into_attribute_value(self) -> Option<Option<String>> {
+        (!self.is_empty()).then(|| Some(self.into()))
+    }
+}
+
+/// A value which can be given to a property in a [`Style`].
+pub trait IntoStyleValue {
+    /// Convert the value into a property's value, or [`None`] if the
+    /// declaration should be left out.
+    fn into_style_value(self) -> Option<String>;
+}
+
+impl<T: IntoStyleValue> IntoStyleValue for Option<T> {
+    fn into_style_value(self) -> Option<String> {
+        self.and_then(IntoStyleValue::into_style_value)
+    }
+}
+
+impl<T: IntoStyleValue> IntoStyleValue for (bool, T) {
+    fn into_style_value(self) -> Option<String> {
+        let (condition, value) = self;
+        condition.then(|| value.into_style_value()).flatten()
+    }
+}
+
+macro_rules! impl_into_style_value {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl IntoStyleValue for $ty {
+                fn into_style_value(self) -> Option<String> {
+                    Some(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+with_text_types!(impl_into_style_value!);
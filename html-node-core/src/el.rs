@@ -0,0 +1,75 @@
+//! ```rust
+//! use html_node_core::{el, Node, Text};
+//!
+//! let link = el::a()
+//!     .attr("href", "/")
+//!     .child(el::img().attr("src", "/logo.png"))
+//!     .child(Text::from("home"));
+//!
+//! assert_eq!(
+//!     Node::from(link).to_string(),
+//!     r#"<a href="/"><img src="/logo.png">home</a>"#,
+//! );
+//! ```
+
+use crate::Element;
+
+macro_rules! elements {
+    ($($name:ident)*) => {
+        $(
+            #[doc = concat!("A `<", stringify!($name), ">` element, with no attributes or children.")]
+            ///
+            /// See [`Element::new`].
+            #[must_use]
+            pub fn $name() -> Element {
+                Element::new(stringify!($name))
+            }
+        )*
+    };
+}
+
+elements! {
+    // main root
+    html
+
+    // document metadata
+    base head link meta style title
+
+    // sectioning root
+    body
+
+    // content sectioning
+    address article aside footer header h1 h2 h3 h4 h5 h6 hgroup main nav section search
+
+    // text content
+    blockquote dd div dl dt figcaption figure hr li menu ol p pre ul
+
+    // inline text semantics
+    a abbr b bdi bdo br cite code data dfn em i kbd mark q rp rt ruby s samp small span strong
+    sub sup time u var wbr
+
+    // image and multimedia
+    area audio img map track video
+
+    // embedded content
+    embed iframe object picture portal source svg math
+
+    // scripting
+    canvas noscript script
+
+    // demarcating edits
+    del ins
+
+    // table content
+    caption col colgroup table tbody td tfoot th thead tr
+
+    // forms
+    button datalist fieldset form input label legend meter optgroup option output progress
+    select textarea
+
+    // interactive elements
+    details dialog summary
+
+    // web components
+    slot template
+}
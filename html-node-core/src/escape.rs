@@ -0,0 +1,416 @@
+//! Text and attribute values are always HTML-escaped when rendered.
+//!
+//! On top of that, rendering applies the escaping in this module depending on
+//! where the value ends up:
+//!
+//! - [URL attributes](URL_ATTRIBUTES) with an unsafe scheme (like
+//!   `javascript:`) are replaced by [`UNSAFE_URL`]. See [`url`].
+//! - [`Text`](crate::Text) inside `<script>` and `<style>` is not HTML-escaped
+//!   (which would change the code), but anything which would end the element
+//!   early is escaped instead. See [`script`] and [`style`].
+//!
+//! - [`Comment`](crate::Comment)s are escaped with [`comment`], so they can't
+//!   end early.
+//!
+//! [`js_string`] and [`css_string`] can be used to safely put a value into
+//! JavaScript or CSS code, and [`css_declaration`] escapes the properties and
+//! values of a [`Style`](crate::Style).
+//!
+//! When rendering XHTML (see [`Syntax::Xhtml`](crate::render::Syntax::Xhtml)),
+//! text and attribute values are XML-escaped with [`xml_text`] and
+//! [`xml_attribute`] instead.
+
+use std::{borrow::Cow, fmt::Write};
+
+/// The attributes whose values are URLs.
+pub const URL_ATTRIBUTES: &[&str] = &[
+    "action",
+    "background",
+    "cite",
+    "codebase",
+    "data",
+    "formaction",
+    "href",
+    "icon",
+    "longdesc",
+    "manifest",
+    "poster",
+    "src",
+    "xlink:href",
+];
+
+/// The URL schemes which are allowed in [URL attributes](URL_ATTRIBUTES).
+///
+/// Relative URLs (with no scheme) are always allowed, and so are `data:` URLs
+/// of raster images.
+pub const SAFE_URL_SCHEMES: &[&str] = &["ftp", "http", "https", "mailto", "tel"];
+
+/// The value which replaces URLs with an unsafe scheme.
+pub const UNSAFE_URL: &str = "about:invalid#html-node-unsafe-url";
+
+const SAFE_DATA_URL_PREFIXES: &[&str] = &[
+    "data:image/avif",
+    "data:image/bmp",
+    "data:image/gif",
+    "data:image/jpeg",
+    "data:image/png",
+    "data:image/webp",
+];
+
+/// Whether the attribute with the given name is a [URL
+/// attribute](URL_ATTRIBUTES).
+#[must_use]
+pub fn is_url_attribute(name: &str) -> bool {
+    URL_ATTRIBUTES
+        .iter()
+        .any(|attribute| attribute.eq_ignore_ascii_case(name))
+}
+
+/// Whether the URL is relative, or has one of the [`SAFE_URL_SCHEMES`].
+#[must_use]
+pub fn is_safe_url(url: &str) -> bool {
+    let (url, scheme_len) = normalize_url(url);
+
+    scheme_len.is_none_or(|len| {
+        let scheme = &url[..len];
+
+        SAFE_URL_SCHEMES
+            .iter()
+            .any(|safe| safe.eq_ignore_ascii_case(scheme))
+            || SAFE_DATA_URL_PREFIXES.iter().any(|prefix| {
+                url.get(..prefix.len())
+                    .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+            })
+    })
+}
+
+/// Remove the characters browsers ignore in a URL, returning it along with the
+/// length of its scheme (or `None` if it is relative).
+pub(crate) fn normalize_url(url: &str) -> (String, Option<usize>) {
+    // browsers ignore whitespace and control characters in URL schemes,
+    // so `java\tscript:` is still a `javascript:` URL.
+    let url = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+        .collect::<String>();
+
+    let scheme_len = url
+        .find(':')
+        .filter(|&i| !url[..i].contains(['/', '?', '#']));
+
+    (url, scheme_len)
+}
+
+/// Escape a URL attribute value, replacing it with [`UNSAFE_URL`] if it isn't
+/// [safe](is_safe_url).
+#[must_use]
+pub fn url(url: &str) -> &str {
+    if is_safe_url(url) {
+        url
+    } else {
+        UNSAFE_URL
+    }
+}
+
+/// Escape the contents of a `<script>` element.
+///
+/// The code is left as is, except for `</script` and `<!--`, which are
+/// escaped as `<\/script` and `<\!--` so they can't end the element early.
+/// This keeps the meaning of strings, template literals, comments and most
+/// regular expressions, which is where these sequences usually appear.
+///
+/// It doesn't in regular expressions with the `u` or `v` flag, where `\!` is a
+/// syntax error (write `/<\x21--/u` instead), or outside of any of these
+/// (like the HTML-like comment in `a <!-- b`), so code which needs escaping
+/// there fails to run rather than ending the element.
+#[must_use]
+pub fn script(code: &str) -> Cow<'_, str> {
+    escape_raw_text(code, "script", true)
+}
+
+/// Escape the contents of a `<style>` element.
+///
+/// The stylesheet is left as is, except for `</style`, which is escaped as
+/// `<\/style` so it can't end the element early.
+#[must_use]
+pub fn style(stylesheet: &str) -> Cow<'_, str> {
+    escape_raw_text(stylesheet, "style", false)
+}
+
+/// Escape a value as a quoted JavaScript string literal.
+///
+/// The literal is safe to put anywhere in a `<script>` element or in an event
+/// handler attribute (like `onclick`).
+///
+/// ```rust
+/// use html_node_core::escape;
+///
+/// assert_eq!(
+///     escape::js_string("</script>\"hi\"\n"),
+///     r#""\u003C\u002Fscript\u003E\u0022hi\u0022\n""#,
+/// );
+/// ```
+#[must_use]
+pub fn js_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str(r"\\"),
+            '\n' => escaped.push_str(r"\n"),
+            '\r' => escaped.push_str(r"\r"),
+            '\t' => escaped.push_str(r"\t"),
+            '"' | '\'' | '`' | '<' | '>' | '&' | '/' | '\u{2028}' | '\u{2029}' => {
+                let _ = write!(escaped, "\\u{:04X}", u32::from(c));
+            }
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04X}", u32::from(c));
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+/// Escape a value as a quoted CSS string.
+///
+/// The string is safe to put anywhere in a `<style>` element or a `style`
+/// attribute.
+///
+/// ```rust
+/// use html_node_core::escape;
+///
+/// assert_eq!(
+///     escape::css_string("a\"b</style>"),
+///     r#""a\22 b\3C \2F style\3E ""#,
+/// );
+/// ```
+#[must_use]
+pub fn css_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+
+    for c in value.chars() {
+        if c.is_ascii_alphanumeric() || (!c.is_ascii() && !c.is_control()) || c == ' ' {
+            escaped.push(c);
+        } else {
+            // the trailing space ends the escape, and isn't part of the string.
+            let _ = write!(escaped, "\\{:X} ", u32::from(c));
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+/// Escape a property or value of a CSS declaration (like in a `style`
+/// attribute).
+///
+/// `;`, `{`, `}`, `\`, quotes and control characters are escaped, so the value
+/// can't end the declaration (or the declaration block) early, or start a
+/// string which swallows the declarations after it. The `/` of a `/*` and the
+/// `*` of a `*/` are escaped too, so the value can't start (or end) a comment,
+/// but other slashes (like in `12px/1.5`) are kept.
+///
+/// ```rust
+/// use html_node_core::escape;
+///
+/// assert_eq!(escape::css_declaration("red; display: none"), r"red\3B  display: none");
+/// assert_eq!(escape::css_declaration("red /* a */"), r"red \2F * a \2A /");
+/// assert_eq!(escape::css_declaration("\"a'"), r"\22 a\27 ");
+/// assert_eq!(escape::css_declaration("12px/1.5 serif"), "12px/1.5 serif");
+/// ```
+#[must_use]
+pub fn css_declaration(value: &str) -> Cow<'_, str> {
+    let needs_escape = |c: char, next: Option<char>| {
+        matches!(c, ';' | '{' | '}' | '\\' | '"' | '\'')
+            || c.is_control()
+            || matches!((c, next), ('/', Some('*')) | ('*', Some('/')))
+    };
+
+    let mut escaped = None::<String>;
+
+    for (i, c) in value.char_indices() {
+        let next = value[i + c.len_utf8()..].chars().next();
+
+        if needs_escape(c, next) {
+            let escaped = escaped.get_or_insert_with(|| {
+                let mut escaped = String::with_capacity(value.len() + 4);
+                escaped.push_str(&value[..i]);
+                escaped
+            });
+
+            // the trailing space ends the escape, and isn't part of the value.
+            let _ = write!(escaped, "\\{:X} ", u32::from(c));
+        } else if let Some(escaped) = &mut escaped {
+            escaped.push(c);
+        }
+    }
+
+    escaped.map_or(Cow::Borrowed(value), Cow::Owned)
+}
+
+/// Escape the text of a comment.
+///
+/// A space is inserted between any two adjacent hyphens, so the text can't
+/// contain `--` (and so neither `<!--`, `-->` nor `--!>`). A leading `>` or
+/// `->` is harmless, since comments are written with a space after `<!--`.
+///
+/// ```rust
+/// use html_node_core::escape;
+///
+/// assert_eq!(escape::comment("a --> <b> <!-- c"), "a - -> <b> <!- - c");
+/// assert_eq!(escape::comment("---"), "- - -");
+/// ```
+#[must_use]
+pub fn comment(text: &str) -> Cow<'_, str> {
+    if !text.contains("--") {
+        return Cow::Borrowed(text);
+    }
+
+    let mut escaped = String::with_capacity(text.len() + 4);
+
+    for c in text.chars() {
+        if c == '-' && escaped.ends_with('-') {
+            escaped.push(' ');
+        }
+
+        escaped.push(c);
+    }
+
+    Cow::Owned(escaped)
+}
+
+/// Escape the target or data of a processing instruction.
+///
+/// In XML, a processing instruction ends at the first `?>`, and in HTML (where
+/// it is parsed as a bogus comment) at the first `>`, so every `>` is written
+/// as `&gt;`. References aren't decoded in processing instructions, so this
+/// changes the data, but it can't end the instruction early.
+///
+/// ```rust
+/// use html_node_core::escape;
+///
+/// assert_eq!(
+///     escape::processing_instruction("a ?><script>"),
+///     "a ?&gt;<script&gt;",
+/// );
+/// ```
+#[must_use]
+pub fn processing_instruction(text: &str) -> Cow<'_, str> {
+    if text.contains('>') {
+        Cow::Owned(text.replace('>', "&gt;"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Escape text for an XML document.
+///
+/// `&`, `<` and `>` are escaped, and characters which aren't allowed in XML
+/// (like most control characters) are removed.
+///
+/// ```rust
+/// use html_node_core::escape;
+///
+/// assert_eq!(escape::xml_text("a < b && ]]>\u{0}"), "a &lt; b &amp;&amp; ]]&gt;");
+/// ```
+#[must_use]
+pub fn xml_text(text: &str) -> Cow<'_, str> {
+    escape_xml(text, false)
+}
+
+/// Escape a double-quoted XML attribute value.
+///
+/// On top of what [`xml_text`] escapes, `"` and `'` are escaped, and so are
+/// tabs and newlines, which XML parsers would otherwise normalize to spaces.
+///
+/// ```rust
+/// use html_node_core::escape;
+///
+/// assert_eq!(
+///     escape::xml_attribute("say \"hi\"\n"),
+///     "say &quot;hi&quot;&#10;",
+/// );
+/// ```
+#[must_use]
+pub fn xml_attribute(value: &str) -> Cow<'_, str> {
+    escape_xml(value, true)
+}
+
+/// Whether the character is allowed in an XML 1.0 document.
+const fn is_xml_char(c: char) -> bool {
+    matches!(
+        c,
+        '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..
+    )
+}
+
+fn escape_xml(text: &str, attribute: bool) -> Cow<'_, str> {
+    let needs_escape = |c: char| {
+        matches!(c, '&' | '<' | '>')
+            || (attribute && matches!(c, '"' | '\'' | '\t' | '\n' | '\r'))
+            || !is_xml_char(c)
+    };
+
+    let Some(first) = text.find(needs_escape) else {
+        return Cow::Borrowed(text);
+    };
+
+    let mut escaped = String::with_capacity(text.len() + 8);
+    escaped.push_str(&text[..first]);
+
+    for c in text[first..].chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            '\'' if attribute => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' if attribute => {
+                let _ = write!(escaped, "&#{};", u32::from(c));
+            }
+            c if !is_xml_char(c) => {}
+            c => escaped.push(c),
+        }
+    }
+
+    Cow::Owned(escaped)
+}
+
+/// Escape the given raw text by inserting a backslash after the `<` of any
+/// closing tag for the element (and of any comment opener, if `comments` is
+/// set).
+fn escape_raw_text<'a>(text: &'a str, element: &str, comments: bool) -> Cow<'a, str> {
+    let needs_escape = |rest: &str| {
+        rest.strip_prefix("</").map_or_else(
+            || comments && rest.starts_with("<!--"),
+            |after| {
+                after
+                    .get(..element.len())
+                    .is_some_and(|name| name.eq_ignore_ascii_case(element))
+            },
+        )
+    };
+
+    let mut escaped = String::new();
+    let mut last = 0;
+
+    for (i, _) in text.match_indices('<') {
+        if needs_escape(&text[i..]) {
+            escaped.push_str(&text[last..=i]);
+            escaped.push('\\');
+            last = i + 1;
+        }
+    }
+
+    if last == 0 {
+        Cow::Borrowed(text)
+    } else {
+        escaped.push_str(&text[last..]);
+        Cow::Owned(escaped)
+    }
+}
@@ -3,7 +3,7 @@ mod axum {
     use axum::response::{Html, IntoResponse, Response};
 
     #[cfg(feature = "pretty")]
-    use crate::pretty::Pretty;
+    use crate::pretty::{Pretty, PrettyWith};
     use crate::Node;
 
     impl IntoResponse for Node {
@@ -18,4 +18,11 @@ mod axum {
             Html(self.to_string()).into_response()
         }
     }
+
+    #[cfg(feature = "pretty")]
+    impl IntoResponse for PrettyWith {
+        fn into_response(self) -> Response {
+            Html(self.to_string()).into_response()
+        }
+    }
 }
@@ -2,25 +2,91 @@
 
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+/// Call `$callback!` with any types given, followed by the number types, which
+/// are all written with their [`Display`] implementations.
+macro_rules! with_number_types {
+    ($callback:ident! $(, $ty:ty)* $(,)?) => {
+        $callback!(
+            $($ty,)*
+            i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64,
+        );
+    };
+}
+
+/// Like `with_number_types!`, with the string types (and [`char`]) too.
+macro_rules! with_text_types {
+    ($callback:ident! $(, $ty:ty)* $(,)?) => {
+        with_number_types!(
+            $callback!,
+            $($ty,)*
+            &str,
+            String,
+            &String,
+            ::std::borrow::Cow<'_, str>,
+            ::std::boxed::Box<str>,
+            ::std::rc::Rc<str>,
+            ::std::sync::Arc<str>,
+            char,
+        );
+    };
+}
+
+/// Builders for `class` and `style` attribute values.
+mod attribute;
+
+/// Shorthand constructors for [`Element`]s, one per HTML element.
+pub mod el;
+
+/// Escaping for the different contexts values can end up in.
+pub mod escape;
+
 /// HTTP Server integrations.
 mod http;
 
 /// [`Node`] variant definitions.
 mod node;
 
+/// HTML parsing.
+#[cfg(feature = "parse")]
+pub mod parse;
+
 /// Pretty printing utilities.
 #[cfg(feature = "pretty")]
 pub mod pretty;
+/// Pretty printing utilities, used by the alternate (`{:#}`) formatter.
+#[cfg(not(feature = "pretty"))]
+#[allow(dead_code, clippy::redundant_pub_crate)]
+mod pretty;
+
+/// CSS selectors for querying [`Node`] trees.
+pub mod select;
+
+/// Rendering nodes with non-default options.
+pub mod render;
+
+/// HTML sanitization.
+#[cfg(feature = "sanitize")]
+pub mod sanitize;
 
 /// Typed HTML Nodes.
 #[cfg(feature = "typed")]
 pub mod typed;
 
-use std::fmt::{self, Display, Formatter};
+/// Traits and iterators for walking [`Node`] trees.
+pub mod visit;
+
+use std::{
+    fmt::{self, Display, Formatter},
+    io::{self, BufWriter},
+    sync::Arc,
+};
 
-pub use self::node::*;
 #[cfg(feature = "typed")]
 use self::typed::TypedElement;
+pub use self::{
+    attribute::{Classes, IntoStyleValue, Style},
+    node::*,
+};
 
 /// An HTML node.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -40,6 +106,20 @@ pub enum Node {
     /// ```
     Doctype(Doctype),
 
+    /// A CDATA section.
+    ///
+    /// ```html
+    /// <![CDATA[ I'm character data! ]]>
+    /// ```
+    Cdata(Cdata),
+
+    /// A processing instruction.
+    ///
+    /// ```html
+    /// <?xml-stylesheet href="style.css"?>
+    /// ```
+    ProcessingInstruction(ProcessingInstruction),
+
     /// A fragment.
     ///
     /// ```html
@@ -67,6 +147,56 @@ pub enum Node {
     /// ```
     Text(Text),
 
+    /// The contents of a raw text element, like `<script>` or `<style>`.
+    ///
+    /// ```html
+    /// <script>
+    ///     if (a < b) console.log("I'm raw text!");
+    /// </script>
+    /// ```
+    RawText(RawText),
+
+    /// A node which is shared with other trees (or other places in the same
+    /// tree).
+    ///
+    /// Cloning a shared node only clones the [`Arc`], so subtrees which are
+    /// built once (like a page's header and footer) can be reused cheaply,
+    /// including across threads. Shared nodes are transparent: they render
+    /// exactly like the node they contain, and are traversed like a
+    /// [`Node::Fragment`] with it as the only child.
+    ///
+    /// Mutating a shared node (with [`visit::VisitMut`] or
+    /// [`Node::select_mut`]) clones it first if it is shared, so other trees
+    /// aren't affected.
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    ///
+    /// use html_node_core::{el, Node, Text};
+    ///
+    /// let header = Arc::new(Node::from(el::header().child(Text::from("My Site"))));
+    ///
+    /// let page = |content: &str| {
+    ///     Node::from(
+    ///         el::body()
+    ///             .child(Node::Shared(Arc::clone(&header)))
+    ///             .child(el::main().child(Text::from(content))),
+    ///     )
+    /// };
+    ///
+    /// assert_eq!(
+    ///     page("home").to_string(),
+    ///     "<body><header>My Site</header><main>home</main></body>",
+    /// );
+    /// ```
+    Shared(Arc<Self>),
+
+    /// A node which is only built when it is rendered.
+    ///
+    /// See [`Node::lazy`] and [`Node::stream`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Lazy(Lazy),
+
     /// An unsafe text node.
     ///
     /// # Warning
@@ -86,6 +216,67 @@ impl Node {
         element.into_node(children)
     }
 
+    /// Create a [`Node::Lazy`] which calls `f` to build its contents each time
+    /// it is rendered.
+    ///
+    /// ```rust
+    /// use html_node_core::{el, Node, Text};
+    ///
+    /// let node = Node::lazy(|| el::p().child(Text::from("built when rendered")));
+    ///
+    /// assert_eq!(node.to_string(), "<p>built when rendered</p>");
+    /// ```
+    pub fn lazy<F, N>(f: F) -> Self
+    where
+        F: Fn() -> N + Send + Sync + 'static,
+        N: Into<Self>,
+    {
+        Self::Lazy(Lazy::from_fn(f))
+    }
+
+    /// Create a [`Node::Lazy`] which calls `f` each time it is rendered, and
+    /// writes the nodes from the iterator it returns one at a time, without
+    /// collecting them.
+    ///
+    /// ```rust
+    /// use html_node_core::{el, Node, Text};
+    ///
+    /// let rows = Node::stream(|| (1..=3).map(|i| el::li().child(Text::from(i.to_string()))));
+    /// let list = Node::from(el::ul().child(rows));
+    ///
+    /// assert_eq!(list.to_string(), "<ul><li>1</li><li>2</li><li>3</li></ul>");
+    /// ```
+    pub fn stream<F, I>(f: F) -> Self
+    where
+        F: Fn() -> I + Send + Sync + 'static,
+        I: IntoIterator,
+        I::Item: Into<Self>,
+    {
+        Self::Lazy(Lazy::from_iter_fn(f))
+    }
+
+    /// Parse a string of HTML into a [`Node`].
+    ///
+    /// See [`parse::parse`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`parse::ParseError`] if the input isn't well-formed HTML.
+    #[cfg(feature = "parse")]
+    pub fn parse(html: &str) -> Result<Self, parse::ParseError> {
+        parse::parse(html)
+    }
+
+    /// Sanitize the node according to the given policy, keeping only the
+    /// elements, attributes and URL schemes it allows.
+    ///
+    /// See [`sanitize::Policy`] for more information.
+    #[cfg(feature = "sanitize")]
+    #[must_use]
+    pub fn sanitize(self, policy: &sanitize::Policy) -> Self {
+        policy.sanitize(self)
+    }
+
     /// Wrap the node in a pretty-printing wrapper.
     #[cfg(feature = "pretty")]
     #[must_use]
@@ -93,13 +284,94 @@ impl Node {
         self.into()
     }
 
+    /// Wrap the node in a pretty-printing wrapper, using the given
+    /// configuration.
+    #[cfg(feature = "pretty")]
+    #[must_use]
+    pub fn pretty_with(self, config: pretty::PrettyConfig) -> pretty::PrettyWith {
+        pretty::PrettyWith::new(self, config)
+    }
+
+    /// Write the node to an [`io::Write`] sink.
+    ///
+    /// The tree is walked and written out piece by piece, so the document is
+    /// never built up as a [`String`] first. The output is byte-for-byte the
+    /// same as the [`Display`] output.
+    ///
+    /// Many small writes are made, so an unbuffered sink (like a
+    /// [`File`](std::fs::File)) should be wrapped in a [`BufWriter`], or
+    /// written to with [`Node::write_buffered`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error returned by `writer`.
+    pub fn write_to<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "{self}")
+    }
+
+    /// Render the node to a [`String`], using the given options.
+    ///
+    /// With the default options, this is the same as the [`Display`] output.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`render::RenderError`] if the node can't be rendered with
+    /// the given options.
+    pub fn render(&self, options: &render::RenderOptions) -> Result<String, render::RenderError> {
+        render::render(self, options)
+    }
+
+    /// Render the node to an [`io::Write`] sink, using the given options.
+    ///
+    /// See [`Node::write_to`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`render::RenderError`] if the node can't be rendered with
+    /// the given options, or if writing to `writer` fails.
+    pub fn render_to<W: io::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        options: &render::RenderOptions,
+    ) -> Result<(), render::RenderError> {
+        render::render_to(self, writer, options)
+    }
+
+    /// Write the node to an [`io::Write`] sink, pretty printed.
+    ///
+    /// The output is byte-for-byte the same as the alternate (`{:#}`)
+    /// [`Display`] output.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error returned by `writer`.
+    pub fn write_pretty_to<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "{self:#}")
+    }
+
+    /// Write the node to an [`io::Write`] sink through a [`BufWriter`],
+    /// returning the sink once everything has been flushed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error returned by `writer`, including when flushing.
+    pub fn write_buffered<W: io::Write>(&self, writer: W) -> io::Result<W> {
+        let mut writer = BufWriter::new(writer);
+        self.write_to(&mut writer)?;
+
+        writer.into_inner().map_err(io::IntoInnerError::into_error)
+    }
+
     /// Borrow the children of the node, if it is an element (with children) or
     /// a fragment.
+    ///
+    /// The only child of a [`Node::Shared`] is the node it contains.
     #[must_use]
     pub fn as_children(&self) -> Option<&[Self]> {
         match self {
             Self::Fragment(fragment) => Some(&fragment.children),
             Self::Element(element) => element.children.as_deref(),
+            Self::Shared(shared) => Some(std::slice::from_ref(&**shared)),
             _ => None,
         }
     }
@@ -111,11 +383,15 @@ impl Node {
 
     /// The children of the node, if it is an element (with children) or
     /// a fragment.
+    ///
+    /// The only child of a [`Node::Shared`] is the node it contains, which is
+    /// cloned if it is still shared.
     #[must_use]
     pub fn children(self) -> Option<Vec<Self>> {
         match self {
             Self::Fragment(fragment) => Some(fragment.children),
             Self::Element(element) => element.children,
+            Self::Shared(shared) => Some(vec![Arc::unwrap_or_clone(shared)]),
             _ => None,
         }
     }
@@ -125,6 +401,114 @@ impl Node {
         self.children().unwrap_or_default().into_iter()
     }
 
+    /// Iterate over the node and all of its descendants depth-first
+    /// (pre-order), along with the path of parents leading to each of them.
+    ///
+    /// ```rust
+    /// use html_node_core::{Element, Node, Text};
+    ///
+    /// let node = Node::from(Element {
+    ///     name: "ul".into(),
+    ///     attributes: Vec::new(),
+    ///     children: Some(vec![
+    ///         Element {
+    ///             name: "li".into(),
+    ///             attributes: Vec::new(),
+    ///             children: Some(vec![Text::from("a").into()]),
+    ///         }
+    ///         .into(),
+    ///         Element {
+    ///             name: "li".into(),
+    ///             attributes: Vec::new(),
+    ///             children: Some(vec![Text::from("b").into()]),
+    ///         }
+    ///         .into(),
+    ///     ]),
+    /// });
+    ///
+    /// let dfs = node
+    ///     .dfs()
+    ///     .map(|visited| (visited.depth(), visited.node.to_string()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     dfs,
+    ///     [
+    ///         (0, "<ul><li>a</li><li>b</li></ul>".into()),
+    ///         (1, "<li>a</li>".into()),
+    ///         (2, "a".into()),
+    ///         (1, "<li>b</li>".into()),
+    ///         (2, "b".into()),
+    ///     ],
+    /// );
+    /// ```
+    pub fn dfs(&self) -> visit::Dfs<'_> {
+        visit::Dfs::new(self)
+    }
+
+    /// Iterate over the node and all of its descendants breadth-first
+    /// (level-order), along with the path of parents leading to each of them.
+    pub fn bfs(&self) -> visit::Bfs<'_> {
+        visit::Bfs::new(self)
+    }
+
+    /// Find the elements in the tree (including this node) which match a CSS
+    /// selector, in document order.
+    ///
+    /// See [`select::Selector`] for the supported syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`select::SelectorError`] if the selector is invalid.
+    pub fn select(&self, selector: &str) -> Result<Vec<&Element>, select::SelectorError> {
+        Ok(select::Selector::parse(selector)?.select(self).collect())
+    }
+
+    /// Find the first element in the tree (including this node) which matches
+    /// a CSS selector, in document order.
+    ///
+    /// See [`select::Selector`] for the supported syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`select::SelectorError`] if the selector is invalid.
+    pub fn select_first(&self, selector: &str) -> Result<Option<&Element>, select::SelectorError> {
+        Ok(select::Selector::parse(selector)?.select_first(self))
+    }
+
+    /// Find the first element in the tree (including this node) which matches
+    /// a CSS selector, in document order, borrowed mutably.
+    ///
+    /// See [`select::Selector`] for the supported syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`select::SelectorError`] if the selector is invalid.
+    pub fn select_first_mut(
+        &mut self,
+        selector: &str,
+    ) -> Result<Option<&mut Element>, select::SelectorError> {
+        Ok(select::Selector::parse(selector)?.select_first_mut(self))
+    }
+
+    /// Call `f` on every element in the tree (including this node) which
+    /// matches a CSS selector.
+    ///
+    /// See [`select::Selector::select_mut`] for the order `f` is called in.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`select::SelectorError`] if the selector is invalid, in
+    /// which case `f` is never called.
+    pub fn select_mut(
+        &mut self,
+        selector: &str,
+        f: impl FnMut(&mut Element),
+    ) -> Result<(), select::SelectorError> {
+        select::Selector::parse(selector)?.select_mut(self, f);
+        Ok(())
+    }
+
     /// Try to get this node as a [`Comment`], if it is one.
     #[must_use]
     pub const fn as_comment(&self) -> Option<&Comment> {
@@ -145,6 +529,26 @@ impl Node {
         }
     }
 
+    /// Try to get this node as a [`Cdata`] section, if it is one.
+    #[must_use]
+    pub const fn as_cdata(&self) -> Option<&Cdata> {
+        if let Self::Cdata(cdata) = self {
+            Some(cdata)
+        } else {
+            None
+        }
+    }
+
+    /// Try to get this node as a [`ProcessingInstruction`], if it is one.
+    #[must_use]
+    pub const fn as_processing_instruction(&self) -> Option<&ProcessingInstruction> {
+        if let Self::ProcessingInstruction(processing_instruction) = self {
+            Some(processing_instruction)
+        } else {
+            None
+        }
+    }
+
     /// Try to get this node as a [`Fragment`], if it is one.
     #[must_use]
     pub const fn as_fragment(&self) -> Option<&Fragment> {
@@ -175,6 +579,36 @@ impl Node {
         }
     }
 
+    /// Try to get this node as a [`RawText`], if it is one.
+    #[must_use]
+    pub const fn as_raw_text(&self) -> Option<&RawText> {
+        if let Self::RawText(text) = self {
+            Some(text)
+        } else {
+            None
+        }
+    }
+
+    /// Try to get this node as a [`Lazy`] node, if it is one.
+    #[must_use]
+    pub const fn as_lazy(&self) -> Option<&Lazy> {
+        if let Self::Lazy(lazy) = self {
+            Some(lazy)
+        } else {
+            None
+        }
+    }
+
+    /// Try to get the node this node shares, if it is a [`Node::Shared`].
+    #[must_use]
+    pub const fn as_shared(&self) -> Option<&Arc<Self>> {
+        if let Self::Shared(shared) = self {
+            Some(shared)
+        } else {
+            None
+        }
+    }
+
     /// Try to get this node as an [`UnsafeText`], if it is one.
     #[must_use]
     pub const fn as_unsafe_text(&self) -> Option<&UnsafeText> {
@@ -197,14 +631,29 @@ impl Display for Node {
         match &self {
             Self::Comment(comment) => comment.fmt(f),
             Self::Doctype(doctype) => doctype.fmt(f),
+            Self::Cdata(cdata) => cdata.fmt(f),
+            Self::ProcessingInstruction(processing_instruction) => processing_instruction.fmt(f),
             Self::Fragment(fragment) => fragment.fmt(f),
             Self::Element(element) => element.fmt(f),
             Self::Text(text) => text.fmt(f),
+            Self::RawText(raw_text) => raw_text.fmt(f),
+            Self::Shared(shared) => shared.fmt(f),
+            Self::Lazy(lazy) => lazy.fmt(f),
             Self::UnsafeText(unsafe_text) => unsafe_text.fmt(f),
         }
     }
 }
 
+#[cfg(feature = "parse")]
+impl std::str::FromStr for Node {
+    type Err = parse::ParseError;
+
+    /// Parse a string of HTML into a [`Node`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
 impl<I, N> From<I> for Node
 where
     I: IntoIterator<Item = N>,
@@ -227,6 +676,18 @@ impl From<Doctype> for Node {
     }
 }
 
+impl From<Cdata> for Node {
+    fn from(cdata: Cdata) -> Self {
+        Self::Cdata(cdata)
+    }
+}
+
+impl From<ProcessingInstruction> for Node {
+    fn from(processing_instruction: ProcessingInstruction) -> Self {
+        Self::ProcessingInstruction(processing_instruction)
+    }
+}
+
 impl From<Fragment> for Node {
     fn from(fragment: Fragment) -> Self {
         Self::Fragment(fragment)
@@ -245,6 +706,12 @@ impl From<Text> for Node {
     }
 }
 
+impl From<RawText> for Node {
+    fn from(text: RawText) -> Self {
+        Self::RawText(text)
+    }
+}
+
 impl From<UnsafeText> for Node {
     fn from(text: UnsafeText) -> Self {
         Self::UnsafeText(text)
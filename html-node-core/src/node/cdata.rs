@@ -0,0 +1,46 @@
+use std::fmt::{self, Display, Formatter};
+
+/// A CDATA section.
+///
+/// CDATA sections are only meaningful in foreign content (like inline `<svg>`
+/// and `<math>`) and XHTML. In HTML content, browsers treat them as comments.
+///
+/// ```html
+/// <![CDATA[ I'm character data! ]]>
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cdata {
+    /// The text of the section.
+    ///
+    /// ```html
+    /// <![CDATA[text]]>
+    /// ```
+    pub text: String,
+}
+
+impl Display for Cdata {
+    /// Format as a CDATA section.
+    ///
+    /// A `]]>` in the text would end the section early, and in HTML content
+    /// (where the section is a comment) so would any `>`. So the section is
+    /// split around each `>`, which is written as `&gt;` in between.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<![CDATA[{}]]>",
+            self.text.replace('>', "]]>&gt;<![CDATA[")
+        )
+    }
+}
+
+impl<T> From<T> for Cdata
+where
+    T: Into<String>,
+{
+    /// Create a new CDATA section from anything that can be converted into a
+    /// string.
+    fn from(text: T) -> Self {
+        Self { text: text.into() }
+    }
+}
@@ -1,10 +1,21 @@
-use std::fmt::{self, Display, Formatter};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::escape;
 
 /// A comment.
 ///
 /// ```html
 /// <!-- I'm a comment! -->
 /// ```
+///
+/// Comment text can't safely contain everything: `-->` would end the comment
+/// early, and anything after it would be rendered as markup. When rendered,
+/// comments are escaped with [`escape::comment`], so this can't happen.
+/// [`Comment::try_new`] can be used to reject text which would need escaping
+/// instead.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Comment {
@@ -16,10 +27,74 @@ pub struct Comment {
     pub comment: String,
 }
 
+impl Comment {
+    /// Create a new comment, if the text can be written without escaping.
+    ///
+    /// ```rust
+    /// use html_node_core::{Comment, CommentErrorKind};
+    ///
+    /// assert!(Comment::try_new("rendered in 3ms").is_ok());
+    ///
+    /// let error = Comment::try_new("user: --><script>").unwrap_err();
+    /// assert_eq!(error.kind, CommentErrorKind::ContainsCommentClose);
+    /// assert_eq!(error.offset, 6);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CommentError`] with the first rule the text breaks (see
+    /// [`Comment::validate`]).
+    pub fn try_new(comment: impl Into<String>) -> Result<Self, CommentError> {
+        let comment = Self {
+            comment: comment.into(),
+        };
+
+        comment.validate().map(|()| comment)
+    }
+
+    /// Check that the text of the comment can be written without escaping.
+    ///
+    /// The text must not start with `>` or `->`, and must not contain `<!--`,
+    /// `-->`, `--!>` or any other `--`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CommentError`] with the first rule the text breaks.
+    pub fn validate(&self) -> Result<(), CommentError> {
+        let text = &self.comment;
+
+        if text.starts_with('>') || text.starts_with("->") {
+            return Err(CommentError {
+                kind: CommentErrorKind::StartsWithGreaterThan,
+                offset: 0,
+            });
+        }
+
+        for (offset, _) in text.match_indices(['<', '-']) {
+            let rest = &text[offset..];
+
+            let kind = if rest.starts_with("<!--") {
+                CommentErrorKind::ContainsCommentOpen
+            } else if rest.starts_with("-->") || rest.starts_with("--!>") {
+                CommentErrorKind::ContainsCommentClose
+            } else if rest.starts_with("--") {
+                CommentErrorKind::ContainsDoubleHyphen
+            } else {
+                continue;
+            };
+
+            return Err(CommentError { kind, offset });
+        }
+
+        Ok(())
+    }
+}
+
 impl Display for Comment {
-    /// Format as an HTML comment.
+    /// Format as an HTML comment, escaping the text with
+    /// [`escape::comment`].
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "<!-- {} -->", self.comment)
+        write!(f, "<!-- {} -->", escape::comment(&self.comment))
     }
 }
 
@@ -34,3 +109,50 @@ where
         }
     }
 }
+
+/// An error returned by [`Comment::try_new`] and [`Comment::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentError {
+    /// Which rule the text breaks.
+    pub kind: CommentErrorKind,
+
+    /// The byte offset into the text where the rule is broken.
+    pub offset: usize,
+}
+
+impl Display for CommentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.kind, self.offset)
+    }
+}
+
+impl Error for CommentError {}
+
+/// The kind of a [`CommentError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CommentErrorKind {
+    /// The text starts with `>` or `->`, which would end the comment
+    /// immediately if written without padding.
+    StartsWithGreaterThan,
+
+    /// The text contains `<!--`.
+    ContainsCommentOpen,
+
+    /// The text contains `-->` or `--!>`, which would end the comment early.
+    ContainsCommentClose,
+
+    /// The text contains `--`, which isn't allowed in XML comments.
+    ContainsDoubleHyphen,
+}
+
+impl Display for CommentErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StartsWithGreaterThan => write!(f, "comment starts with `>`"),
+            Self::ContainsCommentOpen => write!(f, "comment contains `<!--`"),
+            Self::ContainsCommentClose => write!(f, "comment contains `-->`"),
+            Self::ContainsDoubleHyphen => write!(f, "comment contains `--`"),
+        }
+    }
+}
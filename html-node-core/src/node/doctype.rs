@@ -1,10 +1,18 @@
-use std::fmt::{self, Display, Formatter};
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
 
 /// A doctype.
 ///
 /// ```html
 /// <!DOCTYPE html>
 /// ```
+///
+/// [`Doctype::parse`] and [`Doctype::validate`] check that the syntax is a
+/// well-formed doctype. Since a `>` would end the doctype early, it is never
+/// written, even if the syntax isn't valid.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Doctype {
@@ -13,13 +21,201 @@ pub struct Doctype {
     /// ```html
     /// <!DOCTYPE syntax>
     /// ```
-    pub syntax: String,
+    ///
+    /// This is a [`Cow`] (rather than a [`String`]) so that the well-known
+    /// doctypes, like [`Doctype::HTML5`], can be constants.
+    pub syntax: Cow<'static, str>,
+}
+
+impl Doctype {
+    /// The HTML 4.01 Strict doctype.
+    pub const HTML4_STRICT: Self = Self::new_static(
+        r#"HTML PUBLIC "-//W3C//DTD HTML 4.01//EN" "http://www.w3.org/TR/html4/strict.dtd""#,
+    );
+    /// The HTML 4.01 Transitional doctype.
+    pub const HTML4_TRANSITIONAL: Self = Self::new_static(
+        r#"HTML PUBLIC "-//W3C//DTD HTML 4.01 Transitional//EN" "http://www.w3.org/TR/html4/loose.dtd""#,
+    );
+    /// The HTML5 doctype.
+    ///
+    /// ```html
+    /// <!DOCTYPE html>
+    /// ```
+    pub const HTML5: Self = Self::new_static("html");
+    /// The XHTML 1.0 Strict doctype.
+    pub const XHTML1_STRICT: Self = Self::new_static(
+        r#"html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN" "http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd""#,
+    );
+    /// The XHTML 1.0 Transitional doctype.
+    pub const XHTML1_TRANSITIONAL: Self = Self::new_static(
+        r#"html PUBLIC "-//W3C//DTD XHTML 1.0 Transitional//EN" "http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd""#,
+    );
+
+    const fn new_static(syntax: &'static str) -> Self {
+        Self {
+            syntax: Cow::Borrowed(syntax),
+        }
+    }
+
+    /// Create an `html` doctype with a public identifier, and optionally a
+    /// system identifier.
+    ///
+    /// ```rust
+    /// use html_node_core::Doctype;
+    ///
+    /// let doctype = Doctype::public(
+    ///     "-//W3C//DTD HTML 4.01//EN",
+    ///     Some("http://www.w3.org/TR/html4/strict.dtd"),
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(
+    ///     doctype.to_string(),
+    ///     r#"<!DOCTYPE html PUBLIC "-//W3C//DTD HTML 4.01//EN" "http://www.w3.org/TR/html4/strict.dtd">"#,
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DoctypeError`] if an identifier contains both kinds of
+    /// quotes, or a `<` or `>`.
+    pub fn public(public_id: &str, system_id: Option<&str>) -> Result<Self, DoctypeError> {
+        let mut syntax = format!("html PUBLIC {}", quote(public_id)?);
+
+        if let Some(system_id) = system_id {
+            syntax.push(' ');
+            syntax.push_str(&quote(system_id)?);
+        }
+
+        Ok(Self {
+            syntax: Cow::Owned(syntax),
+        })
+    }
+
+    /// Create an `html` doctype with a system identifier.
+    ///
+    /// ```rust
+    /// use html_node_core::Doctype;
+    ///
+    /// assert_eq!(
+    ///     Doctype::system("about:legacy-compat").unwrap().to_string(),
+    ///     r#"<!DOCTYPE html SYSTEM "about:legacy-compat">"#,
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DoctypeError`] if the identifier contains both kinds of
+    /// quotes, or a `<` or `>`.
+    pub fn system(system_id: &str) -> Result<Self, DoctypeError> {
+        Ok(Self {
+            syntax: Cow::Owned(format!("html SYSTEM {}", quote(system_id)?)),
+        })
+    }
+
+    /// Create a doctype from its syntax, if it is [valid](Self::validate).
+    ///
+    /// ```rust
+    /// use html_node_core::{Doctype, DoctypeErrorKind};
+    ///
+    /// assert!(Doctype::parse("html").is_ok());
+    ///
+    /// let error = Doctype::parse("html><script>").unwrap_err();
+    /// assert_eq!(error.kind, DoctypeErrorKind::UnexpectedChar('>'));
+    /// assert_eq!(error.offset, 4);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DoctypeError`] if the syntax isn't a valid doctype.
+    pub fn parse(syntax: impl Into<String>) -> Result<Self, DoctypeError> {
+        let doctype = Self {
+            syntax: Cow::Owned(syntax.into()),
+        };
+
+        doctype.validate().map(|()| doctype)
+    }
+
+    /// Check that the syntax is a valid doctype.
+    ///
+    /// The syntax must be a name (like `html`), optionally followed by either
+    /// `PUBLIC` and a quoted public identifier (and optionally a quoted
+    /// system identifier), or `SYSTEM` and a quoted system identifier.
+    /// Keywords are case-insensitive.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DoctypeError`] if the syntax isn't a valid doctype.
+    pub fn validate(&self) -> Result<(), DoctypeError> {
+        Parts::parse(&self.syntax).map(|_| ())
+    }
+
+    /// The same doctype, written in a canonical form.
+    ///
+    /// The name is lowercased, keywords are uppercased, identifiers are
+    /// double-quoted (unless they contain a double quote) and parts are
+    /// separated by a single space.
+    ///
+    /// ```rust
+    /// use html_node_core::Doctype;
+    ///
+    /// let doctype = Doctype::from("  HTML   system 'about:legacy-compat' ");
+    ///
+    /// assert_eq!(
+    ///     doctype.normalize().unwrap().to_string(),
+    ///     r#"<!DOCTYPE html SYSTEM "about:legacy-compat">"#,
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DoctypeError`] if the syntax isn't a valid doctype.
+    pub fn normalize(&self) -> Result<Self, DoctypeError> {
+        let parts = Parts::parse(&self.syntax)?;
+
+        if parts.name.eq_ignore_ascii_case("html")
+            && parts.public_id.is_none()
+            && parts.system_id.is_none()
+        {
+            return Ok(Self::HTML5);
+        }
+
+        let mut syntax = parts.name.to_ascii_lowercase();
+
+        // the identifiers have already been checked by parsing.
+        match (parts.public_id, parts.system_id) {
+            (Some(public_id), system_id) => {
+                syntax.push_str(" PUBLIC ");
+                syntax.push_str(&quote(public_id)?);
+
+                if let Some(system_id) = system_id {
+                    syntax.push(' ');
+                    syntax.push_str(&quote(system_id)?);
+                }
+            }
+            (None, Some(system_id)) => {
+                syntax.push_str(" SYSTEM ");
+                syntax.push_str(&quote(system_id)?);
+            }
+            (None, None) => {}
+        }
+
+        Ok(Self {
+            syntax: Cow::Owned(syntax),
+        })
+    }
 }
 
 impl Display for Doctype {
     /// Format as an HTML doctype element.
+    ///
+    /// Any `>` in the syntax is left out, since it would end the doctype.
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "<!DOCTYPE {}>", self.syntax)
+        if self.syntax.contains('>') {
+            write!(f, "<!DOCTYPE {}>", self.syntax.replace('>', ""))
+        } else {
+            write!(f, "<!DOCTYPE {}>", self.syntax)
+        }
     }
 }
 
@@ -31,7 +227,206 @@ where
     /// from anything that can be converted into a string.
     fn from(syntax: S) -> Self {
         Self {
-            syntax: syntax.into(),
+            syntax: Cow::Owned(syntax.into()),
+        }
+    }
+}
+
+/// An error returned when a [`Doctype`]'s syntax isn't valid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoctypeError {
+    /// What went wrong.
+    pub kind: DoctypeErrorKind,
+
+    /// The byte offset into the syntax where the error occurred.
+    pub offset: usize,
+}
+
+impl Display for DoctypeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.kind, self.offset)
+    }
+}
+
+impl Error for DoctypeError {}
+
+/// The kind of a [`DoctypeError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DoctypeErrorKind {
+    /// The syntax ended where more was expected.
+    UnexpectedEof,
+
+    /// A character which isn't allowed at this point in the syntax.
+    UnexpectedChar(char),
+
+    /// A keyword other than `PUBLIC` or `SYSTEM` after the name.
+    UnknownKeyword(String),
+}
+
+impl Display for DoctypeErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof => write!(f, "unexpected end of doctype"),
+            Self::UnexpectedChar(c) => write!(f, "unexpected character `{c}`"),
+            Self::UnknownKeyword(keyword) => write!(f, "unknown doctype keyword `{keyword}`"),
+        }
+    }
+}
+
+/// Quote an identifier, preferring double quotes.
+fn quote(id: &str) -> Result<String, DoctypeError> {
+    if let Some(offset) = id.find(['<', '>']) {
+        return Err(DoctypeError {
+            kind: DoctypeErrorKind::UnexpectedChar(id[offset..].chars().next().unwrap_or('>')),
+            offset,
+        });
+    }
+
+    if !id.contains('"') {
+        Ok(format!("\"{id}\""))
+    } else if !id.contains('\'') {
+        Ok(format!("'{id}'"))
+    } else {
+        Err(DoctypeError {
+            kind: DoctypeErrorKind::UnexpectedChar('\''),
+            offset: id.find('\'').unwrap_or_default(),
+        })
+    }
+}
+
+/// The parts of a doctype's syntax.
+struct Parts<'s> {
+    name: &'s str,
+    public_id: Option<&'s str>,
+    system_id: Option<&'s str>,
+}
+
+impl<'s> Parts<'s> {
+    fn parse(syntax: &'s str) -> Result<Self, DoctypeError> {
+        let mut parser = PartsParser { syntax, pos: 0 };
+
+        parser.skip_whitespace();
+        let name =
+            parser.word(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))?;
+
+        let mut parts = Self {
+            name,
+            public_id: None,
+            system_id: None,
+        };
+
+        if parser.skip_whitespace() && !parser.is_eof() {
+            let keyword_start = parser.pos;
+            let keyword = parser.word(|c| c.is_ascii_alphabetic())?;
+
+            if keyword.eq_ignore_ascii_case("public") {
+                parser.skip_whitespace();
+                parts.public_id = Some(parser.quoted()?);
+
+                if parser.skip_whitespace() && !parser.is_eof() {
+                    parts.system_id = Some(parser.quoted()?);
+                }
+            } else if keyword.eq_ignore_ascii_case("system") {
+                parser.skip_whitespace();
+                parts.system_id = Some(parser.quoted()?);
+            } else {
+                return Err(DoctypeError {
+                    kind: DoctypeErrorKind::UnknownKeyword(keyword.into()),
+                    offset: keyword_start,
+                });
+            }
+
+            parser.skip_whitespace();
+        }
+
+        if let Some(c) = parser.peek() {
+            return Err(parser.error(DoctypeErrorKind::UnexpectedChar(c)));
+        }
+
+        Ok(parts)
+    }
+}
+
+struct PartsParser<'s> {
+    syntax: &'s str,
+    pos: usize,
+}
+
+impl<'s> PartsParser<'s> {
+    fn rest(&self) -> &'s str {
+        &self.syntax[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    const fn is_eof(&self) -> bool {
+        self.pos == self.syntax.len()
+    }
+
+    const fn error(&self, kind: DoctypeErrorKind) -> DoctypeError {
+        DoctypeError {
+            kind,
+            offset: self.pos,
+        }
+    }
+
+    /// Skip whitespace, returning whether there was any.
+    fn skip_whitespace(&mut self) -> bool {
+        let rest = self.rest();
+        let len = rest.len()
+            - rest
+                .trim_start_matches(|c: char| c.is_ascii_whitespace())
+                .len();
+        self.pos += len;
+        len > 0
+    }
+
+    /// Parse a non-empty word made of characters matching `is_word_char`.
+    fn word(&mut self, is_word_char: impl Fn(char) -> bool) -> Result<&'s str, DoctypeError> {
+        let rest = self.rest();
+        let len = rest.find(|c| !is_word_char(c)).unwrap_or(rest.len());
+
+        if len == 0 {
+            return Err(self.error(self.peek().map_or(
+                DoctypeErrorKind::UnexpectedEof,
+                DoctypeErrorKind::UnexpectedChar,
+            )));
+        }
+
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Parse a single- or double-quoted identifier, which can't contain `<` or
+    /// `>`.
+    fn quoted(&mut self) -> Result<&'s str, DoctypeError> {
+        let quote = match self.peek() {
+            Some(quote @ ('"' | '\'')) => quote,
+            Some(c) => return Err(self.error(DoctypeErrorKind::UnexpectedChar(c))),
+            None => return Err(self.error(DoctypeErrorKind::UnexpectedEof)),
+        };
+
+        self.pos += 1;
+        let rest = self.rest();
+
+        match rest.find([quote, '<', '>']) {
+            Some(len) if rest[len..].starts_with(quote) => {
+                self.pos += len + 1;
+                Ok(&rest[..len])
+            }
+            Some(len) => {
+                self.pos += len;
+                Err(self.error(DoctypeErrorKind::UnexpectedChar(
+                    rest[len..].chars().next().unwrap_or(quote),
+                )))
+            }
+            None => {
+                self.pos = self.syntax.len();
+                Err(self.error(DoctypeErrorKind::UnexpectedEof))
+            }
         }
     }
 }
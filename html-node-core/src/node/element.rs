@@ -1,10 +1,61 @@
 use std::fmt::{self, Display, Formatter};
 
-use super::write_children;
-use crate::Node;
+use crate::{
+    pretty::{PrettyConfig, Printer},
+    render::{RenderOptions, Renderer},
+    Node,
+};
+
+/// The elements which have no children and no closing tag, like `<br>`.
+///
+/// From: <https://html.spec.whatwg.org/dev/syntax.html#void-elements>
+pub const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// The elements whose contents are raw text, which isn't HTML-escaped.
+///
+/// From: <https://html.spec.whatwg.org/dev/syntax.html#raw-text-elements>
+pub const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
+/// The elements whose contents are text, which can contain character
+/// references but no elements.
+///
+/// From: <https://html.spec.whatwg.org/dev/syntax.html#escapable-raw-text-elements>
+pub const ESCAPABLE_RAW_TEXT_ELEMENTS: &[&str] = &["textarea", "title"];
+
+/// Whether the element with the given name (compared case-insensitively) is a
+/// [void element](VOID_ELEMENTS).
+#[must_use]
+pub fn is_void_element(name: &str) -> bool {
+    is_one_of(VOID_ELEMENTS, name)
+}
+
+/// Whether the element with the given name (compared case-insensitively) is a
+/// [raw text element](RAW_TEXT_ELEMENTS).
+#[must_use]
+pub fn is_raw_text_element(name: &str) -> bool {
+    is_one_of(RAW_TEXT_ELEMENTS, name)
+}
+
+/// Whether the element with the given name (compared case-insensitively) is
+/// an [escapable raw text element](ESCAPABLE_RAW_TEXT_ELEMENTS).
+#[must_use]
+pub fn is_escapable_raw_text_element(name: &str) -> bool {
+    is_one_of(ESCAPABLE_RAW_TEXT_ELEMENTS, name)
+}
+
+fn is_one_of(names: &[&str], name: &str) -> bool {
+    names.iter().any(|other| other.eq_ignore_ascii_case(name))
+}
 
 /// An element.
 ///
+/// Void elements (see [`is_void_element`]) are always rendered without a
+/// closing tag, ignoring any children, and every other element is always
+/// rendered with one, even if [`Element::children`] is [`None`].
+///
 /// ```html
 /// <div class="container">
 ///     I'm in an element!
@@ -35,31 +86,399 @@ pub struct Element {
     ///     <child>I'm another child!</child>
     /// </div>
     /// ```
+    ///
+    /// This is [`None`] for void elements.
     pub children: Option<Vec<Node>>,
 }
 
-impl Display for Element {
-    /// Format as an HTML element.
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "<{}", self.name)?;
+impl Element {
+    /// Create a new element with the given name, no attributes and no
+    /// children.
+    ///
+    /// [`Element::children`] is [`None`] for void elements (see
+    /// [`is_void_element`]), and empty for every other element.
+    ///
+    /// The other methods taking and returning `self` can be chained to build
+    /// up the element, producing the same element as the `html!` macro:
+    ///
+    /// ```rust
+    /// use html_node_core::{Element, Node, Text};
+    ///
+    /// let list = Element::new("ul")
+    ///     .attr("id", "list")
+    ///     .class("items")
+    ///     .child(Element::new("li").child(Text::from("first")))
+    ///     .children(["second", "third"].map(|item| Element::new("li").child(Text::from(item))))
+    ///     .child(Element::new("hr"));
+    ///
+    /// assert_eq!(
+    ///     Node::from(list).to_string(),
+    ///     r#"<ul id="list" class="items"><li>first</li><li>second</li><li>third</li><hr></ul>"#,
+    /// );
+    /// ```
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        let children = (!is_void_element(&name)).then(Vec::new);
+
+        Self {
+            name,
+            attributes: Vec::new(),
+            children,
+        }
+    }
+
+    /// Add an attribute with a value.
+    #[must_use]
+    pub fn attr(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.push((key.into(), Some(value.into())));
+        self
+    }
+
+    /// Add a boolean attribute (one with no value, like `disabled`), if
+    /// `present` is `true`.
+    #[must_use]
+    pub fn bool_attr(mut self, key: impl Into<String>, present: bool) -> Self {
+        if present {
+            self.attributes.push((key.into(), None));
+        }
+
+        self
+    }
+
+    /// Add one or more (whitespace-separated) classes.
+    ///
+    /// See [`Element::add_class`].
+    #[must_use]
+    pub fn class(mut self, classes: &str) -> Self {
+        self.add_class(classes);
+        self
+    }
+
+    /// Set a property in the `style` attribute.
+    ///
+    /// See [`Element::set_style`].
+    #[must_use]
+    pub fn style(mut self, property: &str, value: &str) -> Self {
+        self.set_style(property, value);
+        self
+    }
+
+    /// Add a child.
+    ///
+    /// Children of void elements are never rendered.
+    #[must_use]
+    pub fn child(mut self, child: impl Into<Node>) -> Self {
+        self.children
+            .get_or_insert_with(Vec::new)
+            .push(child.into());
+        self
+    }
+
+    /// Add several children.
+    ///
+    /// Children of void elements are never rendered.
+    #[must_use]
+    pub fn children<I, N>(mut self, children: I) -> Self
+    where
+        I: IntoIterator<Item = N>,
+        N: Into<Node>,
+    {
+        self.children
+            .get_or_insert_with(Vec::new)
+            .extend(children.into_iter().map(Into::into));
+        self
+    }
+
+    /// Remove the children of the element, setting [`Element::children`] to
+    /// [`None`] like it is for void elements.
+    #[must_use]
+    pub fn void(mut self) -> Self {
+        self.children = None;
+        self
+    }
+
+    /// Whether the element is a [void element](VOID_ELEMENTS).
+    #[must_use]
+    pub fn is_void(&self) -> bool {
+        is_void_element(&self.name)
+    }
 
-        for (key, value) in &self.attributes {
-            write!(f, " {key}")?;
+    /// The value of the attribute with the given name (compared
+    /// case-insensitively), if the element has it.
+    ///
+    /// Attributes with no value (like `<input disabled>`) have an empty value.
+    #[must_use]
+    pub fn get_attr(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(other, _)| other.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_deref().unwrap_or_default())
+    }
+
+    /// Whether the element has an attribute with the given name (compared
+    /// case-insensitively).
+    #[must_use]
+    pub fn has_attr(&self, key: &str) -> bool {
+        self.attributes
+            .iter()
+            .any(|(other, _)| other.eq_ignore_ascii_case(key))
+    }
+
+    /// Set the value of an attribute, replacing any existing attributes with
+    /// the same name (compared case-insensitively).
+    ///
+    /// ```rust
+    /// use html_node_core::Element;
+    ///
+    /// let mut a = Element::from("a");
+    /// a.set_attr("href", "/");
+    /// a.set_attr("HREF", "/home");
+    ///
+    /// assert_eq!(a.attributes, [("href".into(), Some("/home".into()))]);
+    /// ```
+    pub fn set_attr(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.set_attr_value(key.into(), Some(value.into()));
+    }
 
-            if let Some(value) = value {
-                let encoded_value = html_escape::encode_double_quoted_attribute(value);
-                write!(f, r#"="{encoded_value}""#)?;
+    /// Add or remove a boolean attribute (one with no value, like
+    /// `disabled`), replacing any existing attributes with the same name
+    /// (compared case-insensitively).
+    pub fn set_bool_attr(&mut self, key: impl Into<String>, present: bool) {
+        let key = key.into();
+
+        if present {
+            self.set_attr_value(key, None);
+        } else {
+            self.remove_attr(&key);
+        }
+    }
+
+    /// Remove all the attributes with the given name (compared
+    /// case-insensitively), returning the value of the first one.
+    ///
+    /// Attributes with no value have an empty value.
+    pub fn remove_attr(&mut self, key: &str) -> Option<String> {
+        let mut removed = None;
+
+        self.attributes.retain_mut(|(other, value)| {
+            if !other.eq_ignore_ascii_case(key) {
+                return true;
+            }
+
+            if removed.is_none() {
+                removed = Some(value.take().unwrap_or_default());
+            }
+
+            false
+        });
+
+        removed
+    }
+
+    /// Add attributes, like spreading them onto the element with
+    /// `{..attributes}` in the `html!` macro.
+    ///
+    /// Each attribute replaces any existing attributes with the same name
+    /// (compared case-insensitively), except for `class`, whose classes are
+    /// added to the existing ones.
+    ///
+    /// ```rust
+    /// use html_node_core::Element;
+    ///
+    /// let mut button = Element::from("button")
+    ///     .attr("class", "btn")
+    ///     .attr("type", "button");
+    /// button.spread_attrs([("class", Some("primary")), ("type", Some("submit"))]);
+    ///
+    /// assert_eq!(button.get_attr("class"), Some("btn primary"));
+    /// assert_eq!(button.get_attr("type"), Some("submit"));
+    /// ```
+    pub fn spread_attrs<K, V>(&mut self, attributes: impl IntoIterator<Item = (K, Option<V>)>)
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        spread_attributes(&mut self.attributes, attributes);
+    }
+
+    /// Iterate over the classes of the element.
+    pub fn classes(&self) -> impl Iterator<Item = &str> {
+        self.get_attr("class")
+            .unwrap_or_default()
+            .split_ascii_whitespace()
+    }
+
+    /// Whether the element has the given class.
+    #[must_use]
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes().any(|other| other == class)
+    }
+
+    /// Add one or more (whitespace-separated) classes to the element, unless
+    /// it already has them.
+    ///
+    /// ```rust
+    /// use html_node_core::Element;
+    ///
+    /// let mut div = Element::from("div");
+    /// div.add_class("card");
+    /// div.add_class("card wide");
+    ///
+    /// assert_eq!(div.get_attr("class"), Some("card wide"));
+    /// ```
+    pub fn add_class(&mut self, classes: &str) {
+        let mut class_list = self.classes().map(ToOwned::to_owned).collect::<Vec<_>>();
+
+        for class in classes.split_ascii_whitespace() {
+            if !class_list.iter().any(|other| other == class) {
+                class_list.push(class.into());
             }
         }
-        write!(f, ">")?;
 
-        if let Some(children) = &self.children {
-            write_children(f, children, false)?;
+        self.set_class_list(&class_list);
+    }
+
+    /// Remove one or more (whitespace-separated) classes from the element.
+    ///
+    /// The `class` attribute is removed if no classes are left.
+    pub fn remove_class(&mut self, classes: &str) {
+        let class_list = self
+            .classes()
+            .filter(|class| {
+                !classes
+                    .split_ascii_whitespace()
+                    .any(|other| other == *class)
+            })
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
 
-            write!(f, "</{}>", self.name)?;
-        };
+        self.set_class_list(&class_list);
+    }
+
+    /// Add the class if the element doesn't have it, or remove it if it does,
+    /// returning whether the element has it afterwards.
+    pub fn toggle_class(&mut self, class: &str) -> bool {
+        if self.has_class(class) {
+            self.remove_class(class);
+            false
+        } else {
+            self.add_class(class);
+            true
+        }
+    }
+
+    /// The value of a property in the element's `style` attribute.
+    #[must_use]
+    pub fn get_style(&self, property: &str) -> Option<&str> {
+        declarations(self.get_attr("style").unwrap_or_default())
+            .filter(|(other, _)| other.eq_ignore_ascii_case(property))
+            .last()
+            .map(|(_, value)| value)
+    }
+
+    /// Set a property in the element's `style` attribute, replacing its
+    /// existing value if it has one.
+    ///
+    /// ```rust
+    /// use html_node_core::Element;
+    ///
+    /// let mut div = Element::from("div");
+    /// div.set_attr("style", "color: red; margin: 0");
+    /// div.set_style("color", "blue");
+    /// div.set_style("padding", "1em");
+    ///
+    /// assert_eq!(
+    ///     div.get_attr("style"),
+    ///     Some("color: blue; margin: 0; padding: 1em"),
+    /// );
+    /// ```
+    pub fn set_style(&mut self, property: &str, value: &str) {
+        self.merge_style(&format!("{property}: {value}"));
+    }
+
+    /// Merge a list of declarations (like `color: red; margin: 0`) into the
+    /// element's `style` attribute, replacing the values of properties which
+    /// are already set.
+    pub fn merge_style(&mut self, style: &str) {
+        let mut merged = declarations(self.get_attr("style").unwrap_or_default())
+            .map(|(property, value)| (property.to_owned(), value.to_owned()))
+            .collect::<Vec<_>>();
+
+        for (property, value) in declarations(style) {
+            match merged
+                .iter_mut()
+                .find(|(other, _)| other.eq_ignore_ascii_case(property))
+            {
+                Some((_, existing)) => *existing = value.into(),
+                None => merged.push((property.into(), value.into())),
+            }
+        }
 
-        Ok(())
+        self.set_style_declarations(&merged);
+    }
+
+    /// Remove a property from the element's `style` attribute, returning its
+    /// value.
+    ///
+    /// The `style` attribute is removed if no properties are left.
+    pub fn remove_style(&mut self, property: &str) -> Option<String> {
+        let mut removed = None;
+
+        let declarations = declarations(self.get_attr("style").unwrap_or_default())
+            .filter(|(other, value)| {
+                let is_removed = other.eq_ignore_ascii_case(property);
+
+                if is_removed {
+                    removed = Some((*value).to_owned());
+                }
+
+                !is_removed
+            })
+            .map(|(property, value)| (property.to_owned(), value.to_owned()))
+            .collect::<Vec<_>>();
+
+        self.set_style_declarations(&declarations);
+
+        removed
+    }
+
+    fn set_attr_value(&mut self, key: String, value: Option<String>) {
+        set_attribute(&mut self.attributes, key, value);
+    }
+
+    fn set_class_list(&mut self, classes: &[String]) {
+        if classes.is_empty() {
+            self.remove_attr("class");
+        } else {
+            self.set_attr("class", classes.join(" "));
+        }
+    }
+
+    fn set_style_declarations(&mut self, declarations: &[(String, String)]) {
+        if declarations.is_empty() {
+            self.remove_attr("style");
+        } else {
+            let style = declarations
+                .iter()
+                .map(|(property, value)| format!("{property}: {value}"))
+                .collect::<Vec<_>>()
+                .join("; ");
+
+            self.set_attr("style", style);
+        }
+    }
+}
+
+impl Display for Element {
+    /// Format as an HTML element.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return Printer::new(f, &PrettyConfig::default(), &RenderOptions::default())
+                .element(self);
+        }
+
+        Renderer::new(f, &RenderOptions::default()).element(self)
     }
 }
 
@@ -78,3 +497,227 @@ where
         }
     }
 }
+
+/// Add attributes to a list of attributes, like [`Element::spread_attrs`].
+///
+/// Used by the `html!` macro for `{..attributes}`.
+#[doc(hidden)]
+pub fn spread_attributes<K, V>(
+    attributes: &mut Vec<(String, Option<String>)>,
+    spread: impl IntoIterator<Item = (K, Option<V>)>,
+) where
+    K: Into<String>,
+    V: Into<String>,
+{
+    for (key, value) in spread {
+        let (key, value) = (key.into(), value.map(Into::into));
+
+        let existing_class = key
+            .eq_ignore_ascii_case("class")
+            .then(|| {
+                attributes
+                    .iter_mut()
+                    .find(|(other, _)| other.eq_ignore_ascii_case(&key))
+            })
+            .flatten();
+
+        let Some((_, classes)) = existing_class else {
+            set_attribute(attributes, key, value);
+            continue;
+        };
+
+        let classes = classes.get_or_insert_with(String::new);
+
+        for class in value
+            .iter()
+            .flat_map(|value| value.split_ascii_whitespace())
+        {
+            if !classes.split_ascii_whitespace().any(|other| other == class) {
+                if !classes.is_empty() {
+                    classes.push(' ');
+                }
+
+                classes.push_str(class);
+            }
+        }
+    }
+}
+
+/// Set the value of an attribute, replacing any existing attributes with the
+/// same name (compared case-insensitively).
+fn set_attribute(
+    attributes: &mut Vec<(String, Option<String>)>,
+    key: String,
+    value: Option<String>,
+) {
+    let Some(i) = attributes
+        .iter()
+        .position(|(other, _)| other.eq_ignore_ascii_case(&key))
+    else {
+        attributes.push((key, value));
+        return;
+    };
+
+    attributes[i].1 = value;
+
+    let mut j = i + 1;
+    while j < attributes.len() {
+        if attributes[j].0.eq_ignore_ascii_case(&key) {
+            attributes.remove(j);
+        } else {
+            j += 1;
+        }
+    }
+}
+
+/// A value which can be given to an attribute in the `html!` macro.
+///
+/// [`None`] leaves the attribute out, [`Some(None)`](Some) gives an attribute
+/// with no value (like `disabled`), and [`Some(Some(value))`](Some) gives an
+/// attribute with that value.
+///
+/// This is implemented for strings and numbers (which are always present),
+/// [`bool`]s (which give an attribute with no value when `true`, and leave it
+/// out when `false`) and [`Option`]s of any of them (which leave the attribute
+/// out when [`None`]). The `html!` macro converts any other value with
+/// [`ToString`].
+///
+/// Enumerated attributes which take `"true"` and `"false"` (`aria-*`,
+/// `contenteditable`, `draggable` and `spellcheck`) are the exception:
+/// [`bool`]s give them a `"true"` or `"false"` value instead, through
+/// [`into_attribute_value_for`](Self::into_attribute_value_for).
+///
+/// ```rust
+/// use html_node_core::IntoAttributeValue;
+///
+/// assert_eq!("text".into_attribute_value(), Some(Some("text".into())));
+/// assert_eq!(1.5.into_attribute_value(), Some(Some("1.5".into())));
+/// assert_eq!(true.into_attribute_value(), Some(None));
+/// assert_eq!(false.into_attribute_value(), None);
+/// assert_eq!(Some(true).into_attribute_value(), Some(None));
+/// assert_eq!(None::<&str>.into_attribute_value(), None);
+///
+/// assert_eq!(
+///     false.into_attribute_value_for("draggable"),
+///     Some(Some("false".into())),
+/// );
+/// assert_eq!(
+///     Some(true).into_attribute_value_for("aria-hidden"),
+///     Some(Some("true".into())),
+/// );
+/// ```
+pub trait IntoAttributeValue {
+    /// Convert the value into an attribute's value, or [`None`] if the
+    /// attribute should be left out.
+    fn into_attribute_value(self) -> Option<Option<String>>;
+
+    /// Convert the value into the value of the attribute `name`, or [`None`]
+    /// if the attribute should be left out.
+    ///
+    /// By default, this is the same as
+    /// [`into_attribute_value`](Self::into_attribute_value).
+    fn into_attribute_value_for(self, name: &str) -> Option<Option<String>>
+    where
+        Self: Sized,
+    {
+        let _ = name;
+        self.into_attribute_value()
+    }
+}
+
+impl IntoAttributeValue for bool {
+    fn into_attribute_value(self) -> Option<Option<String>> {
+        self.then_some(None)
+    }
+
+    /// Give enumerated attributes (like `draggable`) a `"true"` or `"false"`
+    /// value, and any others no value (or leave them out).
+    fn into_attribute_value_for(self, name: &str) -> Option<Option<String>> {
+        if is_true_false_attribute(name) {
+            Some(Some(self.to_string()))
+        } else {
+            self.into_attribute_value()
+        }
+    }
+}
+
+impl<T: IntoAttributeValue> IntoAttributeValue for Option<T> {
+    fn into_attribute_value(self) -> Option<Option<String>> {
+        self.and_then(IntoAttributeValue::into_attribute_value)
+    }
+
+    fn into_attribute_value_for(self, name: &str) -> Option<Option<String>> {
+        self.and_then(|value| value.into_attribute_value_for(name))
+    }
+}
+
+/// Whether an attribute is an enumerated attribute which takes `"true"` or
+/// `"false"`, rather than a boolean attribute which is present or not.
+fn is_true_false_attribute(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+
+    name.starts_with("aria-")
+        || matches!(
+            name.as_str(),
+            "contenteditable" | "draggable" | "spellcheck"
+        )
+}
+
+macro_rules! impl_into_attribute_value {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl IntoAttributeValue for $ty {
+                fn into_attribute_value(self) -> Option<Option<String>> {
+                    Some(Some(self.to_string()))
+                }
+            }
+        )*
+    };
+}
+
+with_text_types!(impl_into_attribute_value!);
+
+/// Split a list of CSS declarations into (trimmed) properties and values,
+/// skipping any which are empty or malformed.
+///
+/// Semicolons in strings and parentheses (like in `url(...)`) don't end a
+/// declaration.
+fn declarations(style: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut rest = style;
+
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let mut quote = None;
+        let mut depth = 0_usize;
+
+        let end = rest
+            .char_indices()
+            .find(|&(_, c)| {
+                match (quote, c) {
+                    (Some(q), c) if c == q => quote = None,
+                    (None, '"' | '\'') => quote = Some(c),
+                    (None, '(') => depth += 1,
+                    (None, ')') => depth = depth.saturating_sub(1),
+                    (None, ';') if depth == 0 => return true,
+                    _ => {}
+                }
+
+                false
+            })
+            .map_or(rest.len(), |(i, _)| i);
+
+        let declaration = &rest[..end];
+        rest = rest.get(end + 1..).unwrap_or_default();
+
+        Some(declaration)
+    })
+    .filter_map(|declaration| {
+        let (property, value) = declaration.split_once(':')?;
+        let (property, value) = (property.trim(), value.trim());
+
+        (!property.is_empty() && !value.is_empty()).then_some((property, value))
+    })
+}
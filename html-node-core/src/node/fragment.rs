@@ -1,7 +1,10 @@
 use std::fmt::{self, Display, Formatter};
 
-use super::write_children;
-use crate::Node;
+use crate::{
+    pretty::{PrettyConfig, Printer},
+    render::{RenderOptions, Renderer},
+    Node,
+};
 
 /// A fragment.
 ///
@@ -39,7 +42,12 @@ impl Default for Fragment {
 impl Display for Fragment {
     /// Format the fragment's childrent as HTML elements.
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write_children(f, &self.children, true)
+        if f.alternate() {
+            return Printer::new(f, &PrettyConfig::default(), &RenderOptions::default())
+                .children(&self.children);
+        }
+
+        Renderer::new(f, &RenderOptions::default()).children(&self.children)
     }
 }
 
@@ -0,0 +1,134 @@
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    sync::Arc,
+};
+
+use crate::{
+    pretty::{PrettyConfig, Printer},
+    render::{Output, Render, RenderOptions, Renderer},
+    Node,
+};
+
+/// A node which is only built when it is rendered.
+///
+/// Every time the node is rendered, its [`Render`] implementation is called
+/// to write its contents, so expensive subtrees are only built when they are
+/// needed, and large lists can be written one item at a time instead of
+/// being collected first. See [`Node::lazy`] and [`Node::stream`].
+///
+/// Lazy nodes are opaque: they have no children, so traversals, visitors and
+/// selectors don't look inside them. Two lazy nodes are only equal if they
+/// share the same [`Render`] value.
+///
+/// With the `serde` feature, lazy nodes can't be serialized.
+#[derive(Clone)]
+pub struct Lazy(Arc<dyn Render + Send + Sync>);
+
+impl Lazy {
+    /// Create a lazy node which renders `render`.
+    pub fn new(render: impl Render + Send + Sync + 'static) -> Self {
+        Self(Arc::new(render))
+    }
+
+    /// Create a lazy node which calls `f` to build its contents. See
+    /// [`Node::lazy`].
+    pub fn from_fn<F, N>(f: F) -> Self
+    where
+        F: Fn() -> N + Send + Sync + 'static,
+        N: Into<Node>,
+    {
+        Self::new(FromFn(f))
+    }
+
+    /// Create a lazy node which writes the nodes from the iterator `f`
+    /// returns. See [`Node::stream`].
+    pub fn from_iter_fn<F, I>(f: F) -> Self
+    where
+        F: Fn() -> I + Send + Sync + 'static,
+        I: IntoIterator,
+        I::Item: Into<Node>,
+    {
+        Self::new(FromIterFn(f))
+    }
+
+    /// Build the node's contents now.
+    ///
+    /// If the contents are a single node, that node is returned. Otherwise,
+    /// they are wrapped in a [`Node::Fragment`]. Like
+    /// [`Render::into_node`], text written in pieces becomes a single text
+    /// node, and an error from rendering is ignored, keeping the nodes
+    /// written before it.
+    #[must_use]
+    pub fn evaluate(&self) -> Node {
+        let mut nodes = Output::collect(&*self.0);
+
+        if nodes.len() == 1 {
+            nodes.remove(0)
+        } else {
+            Node::from(nodes)
+        }
+    }
+
+    /// The value the node renders.
+    #[must_use]
+    pub fn as_render(&self) -> &(dyn Render + Send + Sync) {
+        &*self.0
+    }
+}
+
+impl Debug for Lazy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Lazy").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Lazy {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Lazy {}
+
+impl Display for Lazy {
+    /// Format the node's contents as HTML.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return Printer::new(f, &PrettyConfig::default(), &RenderOptions::default())
+                .node(&self.evaluate());
+        }
+
+        Renderer::new(f, &RenderOptions::default()).lazy(self)
+    }
+}
+
+/// A [`Render`] implementation for [`Lazy::from_fn`].
+struct FromFn<F>(F);
+
+impl<F, N> Render for FromFn<F>
+where
+    F: Fn() -> N,
+    N: Into<Node>,
+{
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        output.node(&(self.0)().into())
+    }
+}
+
+/// A [`Render`] implementation for [`Lazy::from_iter_fn`].
+struct FromIterFn<F>(F);
+
+impl<F, I> Render for FromIterFn<F>
+where
+    F: Fn() -> I,
+    I: IntoIterator,
+    I::Item: Into<Node>,
+{
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        for node in (self.0)() {
+            output.node(&node.into())?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::escape;
+
+/// A processing instruction.
+///
+/// Like [`Cdata`](crate::Cdata), processing instructions are only meaningful
+/// in XHTML and foreign content.
+///
+/// ```html
+/// <?xml-stylesheet href="style.css"?>
+/// ```
+///
+/// A `?>` (or in HTML, any `>`) in the target or data would end the
+/// instruction early, so they are escaped with
+/// [`escape::processing_instruction`] when rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProcessingInstruction {
+    /// The target of the instruction.
+    ///
+    /// ```html
+    /// <?target data?>
+    /// ```
+    pub target: String,
+
+    /// The data of the instruction.
+    ///
+    /// ```html
+    /// <?target data?>
+    /// ```
+    pub data: String,
+}
+
+impl ProcessingInstruction {
+    /// Create a new processing instruction.
+    ///
+    /// ```rust
+    /// use html_node_core::ProcessingInstruction;
+    ///
+    /// assert_eq!(
+    ///     ProcessingInstruction::new("xml", r#"version="1.0" encoding="UTF-8""#).to_string(),
+    ///     r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+    /// );
+    /// ```
+    #[must_use]
+    pub fn new(target: impl Into<String>, data: impl Into<String>) -> Self {
+        Self {
+            target: target.into(),
+            data: data.into(),
+        }
+    }
+}
+
+impl Display for ProcessingInstruction {
+    /// Format as a processing instruction, escaping its target and data with
+    /// [`escape::processing_instruction`].
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let target = escape::processing_instruction(&self.target);
+
+        if self.data.is_empty() {
+            write!(f, "<?{target}?>")
+        } else {
+            let data = escape::processing_instruction(&self.data);
+            write!(f, "<?{target} {data}?>")
+        }
+    }
+}
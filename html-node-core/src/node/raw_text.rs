@@ -0,0 +1,57 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::escape;
+
+/// The contents of a raw text element, like `<script>` or `<style>`.
+///
+/// Raw text is not HTML-escaped when rendered, so code like `a < b` keeps
+/// working. Anything which would end the element early is still escaped (see
+/// [`escape::script`] and [`escape::style`]). Inside a `<script>` or `<style>`
+/// element, all of its raw text and text children are escaped together, so
+/// this also catches sequences which are split across several nodes. On its
+/// own, which element it is in isn't known, so `</script`, `</style` and
+/// `<!--` are all escaped.
+///
+/// ```rust
+/// use html_node_core::{Element, Node, RawText};
+///
+/// let script = Node::from(
+///     Element::new("script").child(RawText::from("if (a < b) alert('</script>');")),
+/// );
+///
+/// assert_eq!(
+///     script.to_string(),
+///     r"<script>if (a < b) alert('<\/script>');</script>",
+/// );
+/// ```
+///
+/// # Warning
+///
+/// The text is only escaped so that it stays inside its element. Putting
+/// untrusted text into a `<script>` still allows for XSS attacks, see
+/// [`escape::js_string`] for putting values into JavaScript code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawText {
+    /// The text of the node.
+    pub text: String,
+}
+
+impl Display for RawText {
+    /// Format as raw text, escaping anything which could end a `<script>` or
+    /// `<style>` element.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&escape::style(&escape::script(&self.text)))
+    }
+}
+
+impl<T> From<T> for RawText
+where
+    T: Into<String>,
+{
+    /// Create a new raw text node from anything that can be converted into a
+    /// string.
+    fn from(text: T) -> Self {
+        Self { text: text.into() }
+    }
+}
@@ -0,0 +1,567 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::{
+    is_escapable_raw_text_element, is_raw_text_element, is_void_element, Cdata, Comment, Doctype,
+    Element, Fragment, Node, ProcessingInstruction, RawText, Text,
+};
+
+/// An error encountered while parsing HTML.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// What went wrong.
+    pub kind: ParseErrorKind,
+
+    /// The byte offset into the input where the error occurred.
+    pub offset: usize,
+
+    /// The line (starting at 1) where the error occurred.
+    pub line: usize,
+
+    /// The column (starting at 1, counted in characters) where the error
+    /// occurred.
+    pub column: usize,
+}
+
+impl ParseError {
+    fn new(kind: ParseErrorKind, input: &str, offset: usize) -> Self {
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            kind,
+            offset,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}:{}", self.kind, self.line, self.column)
+    }
+}
+
+impl Error for ParseError {}
+
+/// The kind of a [`ParseError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The input ended in the middle of a tag, comment or doctype.
+    UnexpectedEof,
+
+    /// A character which isn't allowed at this point in a tag.
+    UnexpectedChar(char),
+
+    /// An element was opened but never closed.
+    UnclosedElement(String),
+
+    /// A closing tag which doesn't match the currently open element.
+    UnexpectedClosingTag {
+        /// The name of the element which is currently open, if any.
+        expected: Option<String>,
+
+        /// The name in the closing tag.
+        found: String,
+    },
+
+    /// A closing tag for a void element, which can't have one.
+    VoidClosingTag(String),
+
+    /// Markup which isn't supported, like `<!ELEMENT ...>`.
+    Unsupported,
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof => write!(f, "unexpected end of input"),
+            Self::UnexpectedChar(c) => write!(f, "unexpected character `{c}`"),
+            Self::UnclosedElement(name) => write!(f, "unclosed element `{name}`"),
+            Self::UnexpectedClosingTag {
+                expected: Some(expected),
+                found,
+            } => write!(f, "expected closing tag `{expected}`, found `{found}`"),
+            Self::UnexpectedClosingTag {
+                expected: None,
+                found,
+            } => write!(f, "unexpected closing tag `{found}`"),
+            Self::VoidClosingTag(name) => {
+                write!(f, "void element `{name}` can't have a closing tag")
+            }
+            Self::Unsupported => write!(f, "unsupported markup"),
+        }
+    }
+}
+
+/// Parse a string of HTML into a [`Node`].
+///
+/// If the input contains a single top-level node, that node is returned.
+/// Otherwise, the top-level nodes are wrapped in a [`Node::Fragment`].
+///
+/// Parsing is strict: every non-void element must be closed explicitly, and
+/// closing tags must match the element they close.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] (with the position in the input) if the input
+/// isn't well-formed.
+pub fn parse(input: &str) -> Result<Node, ParseError> {
+    Parser::new(input, false).parse().map(into_node)
+}
+
+/// Parse a string of HTML into a [`Node`], recovering from any errors.
+///
+/// Unlike [`parse`], this never fails:
+///
+/// - Elements which are left open are closed at the end of their parent (or the
+///   input), and closing tags which don't match any open element are ignored.
+///   So `<p>a <b>b</p>` is parsed like `<p>a <b>b</b></p>`.
+/// - Comments, `<script>` and `<style>` elements (and the like) which aren't
+///   closed run until the end of the input.
+/// - Other markup declarations (like `<!ELEMENT ...>`) are parsed as comments,
+///   up until the next `>`.
+/// - Anything else which can't be parsed, like an unterminated tag, is kept as
+///   text.
+#[must_use]
+pub fn parse_lenient(input: &str) -> Node {
+    // lenient parsing has no errors to return.
+    Parser::new(input, true)
+        .parse()
+        .map_or(Node::EMPTY, into_node)
+}
+
+fn into_node(mut nodes: Vec<Node>) -> Node {
+    if nodes.len() == 1 {
+        nodes.remove(0)
+    } else {
+        Node::Fragment(Fragment { children: nodes })
+    }
+}
+
+struct Parser<'s> {
+    input: &'s str,
+    pos: usize,
+    lenient: bool,
+}
+
+impl<'s> Parser<'s> {
+    const fn new(input: &'s str, lenient: bool) -> Self {
+        Self {
+            input,
+            pos: 0,
+            lenient,
+        }
+    }
+
+    fn parse(mut self) -> Result<Vec<Node>, ParseError> {
+        let mut root = Vec::new();
+        let mut open = Vec::<(Element, usize)>::new();
+
+        while self.pos < self.input.len() {
+            let start = self.pos;
+
+            let node = match self.node(&mut open, &mut root, start) {
+                Ok(node) => node,
+                // anything else which can't be parsed is kept as text.
+                Err(_) if self.lenient => {
+                    self.pos = start;
+                    Some(Node::Text(Text::from(self.text())))
+                }
+                Err(error) => return Err(error),
+            };
+
+            if let Some(node) = node {
+                append(&mut open, &mut root, node);
+            }
+        }
+
+        if self.lenient {
+            while !open.is_empty() {
+                close_innermost(&mut open, &mut root);
+            }
+        } else if let Some((element, start)) = open.pop() {
+            return Err(self.error(ParseErrorKind::UnclosedElement(element.name), start));
+        }
+
+        Ok(root)
+    }
+
+    /// Parse the next node, or open (or close) an element.
+    fn node(
+        &mut self,
+        open: &mut Vec<(Element, usize)>,
+        root: &mut Vec<Node>,
+        start: usize,
+    ) -> Result<Option<Node>, ParseError> {
+        let node = if self.eat("<!--") {
+            let comment = self.take_until("-->", start)?;
+            Some(Node::Comment(Comment::from(comment.trim())))
+        } else if self.eat_ignore_case("<!doctype") {
+            let syntax = self.take_until(">", start)?;
+            Some(Node::Doctype(Doctype::from(syntax.trim())))
+        } else if self.eat("<![CDATA[") {
+            let text = self.take_until("]]>", start)?;
+            Some(Node::Cdata(Cdata::from(text)))
+        } else if self.eat("<?") {
+            let instruction = self.take_until("?>", start)?;
+            let (target, data) = instruction
+                .split_once(|c: char| c.is_ascii_whitespace())
+                .unwrap_or((instruction, ""));
+            Some(Node::ProcessingInstruction(ProcessingInstruction::new(
+                target,
+                data.trim_start(),
+            )))
+        } else if self.rest().starts_with("<!") {
+            if !self.lenient {
+                return Err(self.error(ParseErrorKind::Unsupported, start));
+            }
+
+            // browsers treat any other `<!` as a comment, up to the next `>`.
+            self.pos += 2;
+            let comment = self.take_until(">", start)?;
+            Some(Node::Comment(Comment::from(comment.trim())))
+        } else if self.is_closing_tag() {
+            self.pos += 2;
+            let name = self.name();
+            self.skip_whitespace();
+            self.expect('>')?;
+
+            let matching = open
+                .iter()
+                .rposition(|(element, _)| element.name.eq_ignore_ascii_case(name))
+                .filter(|&i| self.lenient || i + 1 == open.len());
+
+            match matching {
+                Some(i) => {
+                    while open.len() > i + 1 {
+                        close_innermost(open, root);
+                    }
+
+                    open.pop().map(|(element, _)| closed(element))
+                }
+                None if self.lenient => None,
+                None if !open.is_empty() => {
+                    let expected = open.pop().map(|(element, _)| element.name);
+
+                    return Err(self.error(
+                        ParseErrorKind::UnexpectedClosingTag {
+                            expected,
+                            found: name.into(),
+                        },
+                        start,
+                    ));
+                }
+                None if is_void_element(name) => {
+                    return Err(self.error(ParseErrorKind::VoidClosingTag(name.into()), start))
+                }
+                None => {
+                    return Err(self.error(
+                        ParseErrorKind::UnexpectedClosingTag {
+                            expected: None,
+                            found: name.into(),
+                        },
+                        start,
+                    ))
+                }
+            }
+        } else if self.is_opening_tag() {
+            self.pos += 1;
+            let (element, self_closing) = self.opening_tag()?;
+
+            if is_void_element(&element.name) {
+                Some(Node::Element(element))
+            } else if self_closing {
+                Some(Node::Element(Element {
+                    children: Some(Vec::new()),
+                    ..element
+                }))
+            } else if is_raw_text_element(&element.name) {
+                Some(self.raw_text_element(element, false, start)?)
+            } else if is_escapable_raw_text_element(&element.name) {
+                Some(self.raw_text_element(element, true, start)?)
+            } else {
+                open.push((element, start));
+                None
+            }
+        } else {
+            Some(Node::Text(Text::from(self.text())))
+        };
+
+        Ok(node)
+    }
+
+    fn opening_tag(&mut self) -> Result<(Element, bool), ParseError> {
+        let mut element = Element::from(self.name());
+
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                None => return Err(self.error(ParseErrorKind::UnexpectedEof, self.pos)),
+                Some('>') => {
+                    self.pos += 1;
+                    return Ok((element, false));
+                }
+                Some('/') => {
+                    self.pos += 1;
+                    self.expect('>')?;
+                    return Ok((element, true));
+                }
+                Some(c @ ('"' | '\'' | '<' | '=')) => {
+                    return Err(self.error(ParseErrorKind::UnexpectedChar(c), self.pos))
+                }
+                Some(_) => {
+                    let key = self.name();
+                    self.skip_whitespace();
+
+                    let value = if self.eat("=") {
+                        self.skip_whitespace();
+                        Some(self.attribute_value()?)
+                    } else {
+                        None
+                    };
+
+                    element.attributes.push((key.into(), value));
+                }
+            }
+        }
+    }
+
+    fn attribute_value(&mut self) -> Result<String, ParseError> {
+        let value = match self.peek() {
+            None => return Err(self.error(ParseErrorKind::UnexpectedEof, self.pos)),
+            Some(quote @ ('"' | '\'')) => {
+                let start = self.pos;
+                self.pos += 1;
+
+                let Some(len) = self.rest().find(quote) else {
+                    return Err(self.error(ParseErrorKind::UnexpectedEof, start));
+                };
+
+                let value = &self.rest()[..len];
+                self.pos += len + 1;
+                value
+            }
+            Some(_) => {
+                let len = self
+                    .rest()
+                    .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                    .unwrap_or_else(|| self.rest().len());
+
+                let value = &self.rest()[..len];
+                self.pos += len;
+                value
+            }
+        };
+
+        Ok(html_escape::decode_html_entities(value).into_owned())
+    }
+
+    /// Parse the contents of a raw text element, up until its closing tag.
+    ///
+    /// When parsing leniently, the rest of the input is taken if there is no
+    /// closing tag.
+    fn raw_text_element(
+        &mut self,
+        element: Element,
+        escapable: bool,
+        start: usize,
+    ) -> Result<Node, ParseError> {
+        let rest = self.rest();
+
+        let end = rest
+            .match_indices("</")
+            .map(|(i, _)| i)
+            .find(|&i| {
+                let after = &rest[i + 2..];
+
+                after
+                    .get(..element.name.len())
+                    .is_some_and(|name| name.eq_ignore_ascii_case(&element.name))
+                    && after[element.name.len()..]
+                        .starts_with(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+            })
+            .or_else(|| self.lenient.then_some(rest.len()))
+            .ok_or_else(|| {
+                self.error(ParseErrorKind::UnclosedElement(element.name.clone()), start)
+            })?;
+
+        let text = &rest[..end];
+
+        if end < rest.len() {
+            self.pos += end + 2 + element.name.len();
+            self.skip_whitespace();
+            self.expect('>')?;
+        } else {
+            self.pos = self.input.len();
+        }
+
+        let children = if text.is_empty() {
+            Vec::new()
+        } else if escapable {
+            vec![Node::Text(Text::from(html_escape::decode_html_entities(
+                text,
+            )))]
+        } else {
+            vec![Node::RawText(RawText::from(text))]
+        };
+
+        Ok(Node::Element(Element {
+            children: Some(children),
+            ..element
+        }))
+    }
+
+    /// Parse text, up until the next tag, comment or doctype.
+    fn text(&mut self) -> String {
+        let start = self.pos;
+
+        loop {
+            let first_len = self.peek().map_or(0, char::len_utf8);
+
+            let Some(i) = self.rest()[first_len..].find('<') else {
+                self.pos = self.input.len();
+                break;
+            };
+
+            self.pos += first_len + i;
+
+            if self.is_opening_tag()
+                || self.is_closing_tag()
+                || self.rest().starts_with("<!")
+                || self.rest().starts_with("<?")
+            {
+                break;
+            }
+        }
+
+        html_escape::decode_html_entities(&self.input[start..self.pos]).into_owned()
+    }
+
+    fn name(&mut self) -> &'s str {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_ascii_whitespace() || matches!(c, '/' | '>' | '=' | '<'))
+            .unwrap_or(rest.len());
+
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Take the input up until `end`, skipping past `end`.
+    ///
+    /// When parsing leniently, the rest of the input is taken if there is no
+    /// `end`.
+    fn take_until(&mut self, end: &str, start: usize) -> Result<&'s str, ParseError> {
+        let rest = self.rest();
+
+        let Some(len) = rest.find(end) else {
+            if self.lenient {
+                self.pos = self.input.len();
+                return Ok(rest);
+            }
+
+            return Err(self.error(ParseErrorKind::UnexpectedEof, start));
+        };
+
+        self.pos += len + end.len();
+        Ok(&rest[..len])
+    }
+
+    fn is_opening_tag(&self) -> bool {
+        let mut chars = self.rest().chars();
+        chars.next() == Some('<') && chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+    }
+
+    fn is_closing_tag(&self) -> bool {
+        self.rest()
+            .strip_prefix("</")
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_alphabetic()))
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => Err(self.error(ParseErrorKind::UnexpectedChar(c), self.pos)),
+            None => Err(self.error(ParseErrorKind::UnexpectedEof, self.pos)),
+        }
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_ignore_case(&mut self, s: &str) -> bool {
+        if self
+            .rest()
+            .get(..s.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(s))
+        {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len()
+            - rest
+                .trim_start_matches(|c: char| c.is_ascii_whitespace())
+                .len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn rest(&self) -> &'s str {
+        &self.input[self.pos..]
+    }
+
+    fn error(&self, kind: ParseErrorKind, offset: usize) -> ParseError {
+        ParseError::new(kind, self.input, offset)
+    }
+}
+
+/// Add a node to the innermost open element, or to the root if there isn't
+/// one.
+fn append(open: &mut [(Element, usize)], root: &mut Vec<Node>, node: Node) {
+    let siblings = match open.last_mut() {
+        Some((parent, _)) => parent.children.get_or_insert_with(Vec::new),
+        None => root,
+    };
+
+    siblings.push(node);
+}
+
+/// Close the innermost open element, adding it to its parent.
+fn close_innermost(open: &mut Vec<(Element, usize)>, root: &mut Vec<Node>) {
+    if let Some((element, _)) = open.pop() {
+        append(open, root, closed(element));
+    }
+}
+
+/// An element which has been closed, so has children (even if there are
+/// none).
+fn closed(mut element: Element) -> Node {
+    if element.children.is_none() {
+        element.children = Some(Vec::new());
+    }
+
+    Node::Element(element)
+}
@@ -1,6 +1,157 @@
-use std::fmt::{self, Display, Formatter};
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter, Write},
+};
 
-use crate::Node;
+use crate::{
+    render::{RenderOptions, Renderer},
+    Element, Node,
+};
+
+/// HTML elements which are rendered inline by browsers.
+///
+/// Whitespace around these elements is visible on the rendered page, so they
+/// are kept on the same line as surrounding text when they are in
+/// [`PrettyConfig::inline_elements`].
+pub const INLINE_ELEMENTS: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "br", "cite", "code", "data", "dfn", "em", "i", "img", "kbd",
+    "label", "mark", "q", "s", "samp", "small", "span", "strong", "sub", "sup", "time", "u", "var",
+    "wbr",
+];
+
+/// HTML elements whose contents are whitespace-sensitive.
+///
+/// The contents of these elements are always written exactly as given, without
+/// being re-indented or put on their own lines.
+pub const PREFORMATTED_ELEMENTS: &[&str] = &["pre", "textarea", "script", "style"];
+
+/// Whether the contents of the element with the given name are
+/// whitespace-sensitive.
+///
+/// See [`PREFORMATTED_ELEMENTS`].
+#[must_use]
+pub fn is_preformatted_element(name: &str) -> bool {
+    PREFORMATTED_ELEMENTS
+        .iter()
+        .any(|preformatted| preformatted.eq_ignore_ascii_case(name))
+}
+
+/// Configuration for pretty printing.
+///
+/// The default configuration puts every child on its own line, indented by 4
+/// spaces, which is what the alternate (`{:#}`) formatter uses.
+///
+/// [`PrettyConfig::inline_aware`] keeps inline elements and short text on the
+/// same line, so that pretty printing doesn't add any whitespace which would
+/// be visible on the rendered page.
+///
+/// Whatever the configuration, the contents of [`PREFORMATTED_ELEMENTS`] are
+/// written exactly as given.
+///
+/// ```rust
+/// # #[cfg(feature = "pretty")]
+/// # {
+/// use html_node_core::{pretty::PrettyConfig, Element, Node, Text};
+///
+/// let html = Node::Element(Element {
+///     name: "p".into(),
+///     attributes: Vec::new(),
+///     children: Some(vec![
+///         Text::from("hello, ").into(),
+///         Element {
+///             name: "strong".into(),
+///             attributes: Vec::new(),
+///             children: Some(vec![Text::from("world").into()]),
+///         }
+///         .into(),
+///     ]),
+/// });
+///
+/// let config = PrettyConfig {
+///     indent: "  ".into(),
+///     ..PrettyConfig::inline_aware()
+/// };
+///
+/// assert_eq!(
+///     html.pretty_with(config).to_string(),
+///     "<p>hello, <strong>world</strong></p>",
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrettyConfig {
+    /// The string written once for every level of indentation.
+    pub indent: String,
+
+    /// The maximum width of a line, in characters.
+    ///
+    /// Lines of inline content are only broken where there is already
+    /// whitespace, so lines can still be longer than this. If [`None`], lines
+    /// are never broken.
+    pub max_width: Option<usize>,
+
+    /// The names of the elements which are kept inline with surrounding text,
+    /// instead of being put on their own line.
+    ///
+    /// See [`INLINE_ELEMENTS`].
+    pub inline_elements: Vec<String>,
+
+    /// Whether an element containing only short inline content (which fits
+    /// within [`PrettyConfig::max_width`]) is kept on a single line.
+    ///
+    /// ```html
+    /// <h1>Shopping List</h1>
+    /// ```
+    pub short_text_inline: bool,
+}
+
+impl PrettyConfig {
+    /// A configuration which keeps [`INLINE_ELEMENTS`] and short text inline,
+    /// breaking lines longer than 80 characters.
+    #[must_use]
+    pub fn inline_aware() -> Self {
+        Self {
+            max_width: Some(80),
+            inline_elements: INLINE_ELEMENTS.iter().map(ToString::to_string).collect(),
+            short_text_inline: true,
+            ..Self::default()
+        }
+    }
+
+    fn is_inline(&self, node: &Node) -> bool {
+        match node {
+            // text is only kept together with the content around it when
+            // something can be inline, so the default configuration still puts
+            // each text node on its own line.
+            Node::Text(_) | Node::RawText(_) | Node::UnsafeText(_) => {
+                self.short_text_inline || !self.inline_elements.is_empty()
+            }
+            Node::Element(element) => self
+                .inline_elements
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&element.name)),
+            Node::Comment(_)
+            | Node::Doctype(_)
+            | Node::Cdata(_)
+            | Node::ProcessingInstruction(_)
+            | Node::Fragment(_)
+            | Node::Lazy(_) => false,
+            Node::Shared(shared) => self.is_inline(shared),
+        }
+    }
+}
+
+impl Default for PrettyConfig {
+    fn default() -> Self {
+        Self {
+            indent: "    ".into(),
+            max_width: None,
+            inline_elements: Vec::new(),
+            short_text_inline: false,
+        }
+    }
+}
 
 /// A wrapper around [`Node`] that is always pretty printed.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -37,3 +188,428 @@ where
         Self(node.into())
     }
 }
+
+/// A wrapper around [`Node`] that is always pretty printed with the given
+/// [`PrettyConfig`].
+///
+/// See [`Node::pretty_with`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrettyWith {
+    /// The node to pretty print.
+    pub node: Node,
+
+    /// How to pretty print the node.
+    pub config: PrettyConfig,
+}
+
+impl PrettyWith {
+    /// Create a new pretty wrapper around the given node, using the given
+    /// configuration.
+    pub fn new(node: impl Into<Node>, config: PrettyConfig) -> Self {
+        Self {
+            node: node.into(),
+            config,
+        }
+    }
+
+    /// Extract the inner node.
+    #[must_use]
+    pub fn into_inner(self) -> Node {
+        self.node
+    }
+
+    /// Borrow the inner node.
+    #[must_use]
+    pub const fn as_inner(&self) -> &Node {
+        &self.node
+    }
+}
+
+impl Display for PrettyWith {
+    /// Format as a pretty printed HTML node.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Printer::new(f, &self.config, &RenderOptions::default()).node(&self.node)
+    }
+}
+
+/// Writes nodes pretty printed according to a [`PrettyConfig`].
+///
+/// Indentation is tracked as a depth rather than by re-indenting the output
+/// of each child, so the output is streamed straight to the underlying
+/// writer.
+pub(crate) struct Printer<'a, 'c> {
+    f: &'a mut dyn Write,
+    config: &'c PrettyConfig,
+    options: &'c RenderOptions,
+    depth: usize,
+    column: usize,
+    started: bool,
+    line_pending: bool,
+}
+
+impl<'a, 'c> Printer<'a, 'c> {
+    pub(crate) fn new(
+        f: &'a mut dyn Write,
+        config: &'c PrettyConfig,
+        options: &'c RenderOptions,
+    ) -> Self {
+        Self {
+            f,
+            config,
+            options,
+            depth: 0,
+            column: 0,
+            started: false,
+            line_pending: false,
+        }
+    }
+
+    /// Write any node.
+    pub(crate) fn node(&mut self, node: &Node) -> fmt::Result {
+        match node {
+            Node::Element(element) => self.element(element),
+            Node::Fragment(fragment) => self.children(&fragment.children),
+            Node::Shared(shared) => self.node(shared),
+            Node::Lazy(lazy) => self.node(&lazy.evaluate()),
+            other => write!(self.f, "{other}"),
+        }
+    }
+
+    /// Write an element, with its children on their own lines.
+    pub(crate) fn element(&mut self, element: &Element) -> fmt::Result {
+        let options = self.options;
+        Renderer::new(self, options).opening_tag(element)?;
+
+        if element.is_void() {
+            return Ok(());
+        }
+
+        // elements with no list of children (like `Element::from("div")`)
+        // are closed straight away, but an empty list still gets a line.
+        let Some(children) = element.children.as_deref() else {
+            return Renderer::new(self, options).closing_tag(element);
+        };
+
+        if is_preformatted_element(&element.name) {
+            Renderer::new(&mut Verbatim(self), options).element_children(element, children)?;
+
+            return Renderer::new(self, options).closing_tag(element);
+        }
+
+        let mut flattened = Vec::new();
+        flatten(children, &mut flattened);
+
+        if self.config.short_text_inline
+            && flattened.iter().all(|child| self.config.is_inline(child))
+        {
+            let mut tokens = Vec::new();
+            for child in &flattened {
+                tokenize(child, self.options, &mut tokens);
+            }
+
+            if self.fits(&tokens, element.name.len() + 3) {
+                self.write_tokens(&tokens, false)?;
+
+                return Renderer::new(self, options).closing_tag(element);
+            }
+        }
+
+        self.depth += 1;
+        self.flattened_children(&flattened)?;
+        self.depth -= 1;
+
+        self.newline();
+        Renderer::new(self, options).closing_tag(element)
+    }
+
+    /// Write a list of children, each block child or run of inline children
+    /// on its own line.
+    pub(crate) fn children(&mut self, children: &[Node]) -> fmt::Result {
+        let mut flattened = Vec::new();
+        flatten(children, &mut flattened);
+
+        self.flattened_children(&flattened)
+    }
+
+    fn flattened_children(&mut self, children: &[&Node]) -> fmt::Result {
+        let mut run = Vec::new();
+
+        for child in children {
+            if self.config.is_inline(child) {
+                tokenize(child, self.options, &mut run);
+            } else {
+                self.inline_run(&run)?;
+                run.clear();
+
+                self.newline();
+
+                match child {
+                    Node::Element(element) => self.element(element)?,
+                    Node::Shared(shared) => self.node(shared)?,
+                    Node::Lazy(lazy) => self.node(&lazy.evaluate())?,
+                    other => write!(self, "{other}")?,
+                }
+            }
+        }
+
+        self.inline_run(&run)
+    }
+
+    fn inline_run(&mut self, tokens: &[Token<'_>]) -> fmt::Result {
+        if tokens.is_empty() {
+            return Ok(());
+        }
+
+        self.newline();
+        self.write_tokens(tokens, true)
+    }
+
+    fn write_tokens(&mut self, tokens: &[Token<'_>], allow_breaks: bool) -> fmt::Result {
+        for (i, token) in tokens.iter().enumerate() {
+            match token {
+                Token::Word(word) => self.write_str(word)?,
+                Token::Verbatim(text) => Verbatim(self).write_str(text)?,
+                Token::Space(space) => {
+                    let should_break = allow_breaks
+                        && !space.contains('\n')
+                        && self.config.max_width.is_some_and(|max_width| {
+                            let next_word = tokens[i + 1..]
+                                .iter()
+                                .map_while(|token| match token {
+                                    Token::Word(word) | Token::Verbatim(word) => {
+                                        Some(word.chars().count())
+                                    }
+                                    Token::Space(_) => None,
+                                })
+                                .sum::<usize>();
+
+                            self.column > self.indent_width()
+                                && self.column + space.chars().count() + next_word > max_width
+                        });
+
+                    if should_break {
+                        self.newline();
+                    } else {
+                        self.write_str(space)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether the tokens (and `extra` more characters) fit on the current
+    /// line.
+    fn fits(&self, tokens: &[Token<'_>], extra: usize) -> bool {
+        let mut width = self.column + extra;
+
+        for token in tokens {
+            let (Token::Word(text) | Token::Verbatim(text) | Token::Space(text)) = token;
+
+            if text.contains('\n') {
+                return false;
+            }
+
+            width += text.chars().count();
+        }
+
+        self.config
+            .max_width
+            .is_none_or(|max_width| width <= max_width)
+    }
+
+    fn indent_width(&self) -> usize {
+        self.config.indent.chars().count() * self.depth
+    }
+
+    /// Start a new line before the next content, unless nothing has been
+    /// written yet.
+    const fn newline(&mut self) {
+        if self.started {
+            self.line_pending = true;
+        }
+    }
+
+    fn write_content(&mut self, s: &str) -> fmt::Result {
+        if self.line_pending {
+            self.f.write_char('\n')?;
+
+            for _ in 0..self.depth {
+                self.f.write_str(&self.config.indent)?;
+            }
+
+            self.column = self.indent_width();
+            self.line_pending = false;
+        }
+
+        self.started = true;
+        self.column += s.chars().count();
+
+        self.f.write_str(s)
+    }
+}
+
+impl Write for Printer<'_, '_> {
+    /// Write content, putting each line of it on its own indented line.
+    ///
+    /// Lines are split the same way as [`str::lines`].
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut segments = s.split('\n').peekable();
+
+        while let Some(segment) = segments.next() {
+            let is_last = segments.peek().is_none();
+
+            let segment = if is_last {
+                segment
+            } else {
+                segment.strip_suffix('\r').unwrap_or(segment)
+            };
+
+            if !segment.is_empty() {
+                self.write_content(segment)?;
+            }
+
+            if !is_last {
+                if self.line_pending {
+                    // an empty line
+                    self.write_content("")?;
+                }
+
+                self.started = true;
+                self.line_pending = true;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A piece of inline content.
+enum Token<'n> {
+    /// Content which a line can't be broken within.
+    Word(Cow<'n, str>),
+
+    /// Whitespace from a text node, which a line can be broken at.
+    Space(Cow<'n, str>),
+
+    /// The contents of a preformatted element, which must be written exactly
+    /// as given.
+    Verbatim(Cow<'n, str>),
+}
+
+/// Writes content through a [`Printer`] exactly as given, without indenting
+/// any of its lines.
+struct Verbatim<'p, 'a, 'c>(&'p mut Printer<'a, 'c>);
+
+impl Write for Verbatim<'_, '_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let printer = &mut *self.0;
+
+        if s.is_empty() {
+            return Ok(());
+        }
+
+        printer.started = true;
+        printer.column = match s.rfind('\n') {
+            Some(i) => s[i + 1..].chars().count(),
+            None => printer.column + s.chars().count(),
+        };
+
+        printer.f.write_str(s)
+    }
+}
+
+/// Collect the children, replacing fragments with their own children and
+/// shared nodes with the node they contain.
+fn flatten<'n>(children: &'n [Node], flattened: &mut Vec<&'n Node>) {
+    for child in children {
+        match child {
+            Node::Fragment(fragment) => flatten(&fragment.children, flattened),
+            Node::Shared(shared) => flatten(std::slice::from_ref(&**shared), flattened),
+            child => flattened.push(child),
+        }
+    }
+}
+
+/// Split a node into inline tokens, rendering any elements compactly.
+fn tokenize<'n>(node: &'n Node, options: &RenderOptions, tokens: &mut Vec<Token<'n>>) {
+    match node {
+        Node::Text(text) => {
+            // escaped the same way as when rendering normally, so the
+            // options (like XHTML) are respected.
+            let mut escaped = String::new();
+            let _ = Renderer::new(&mut escaped, options).text(&text.text);
+
+            tokens.extend(split_whitespace(&escaped).map(|(is_space, text)| {
+                if is_space {
+                    Token::Space(Cow::Owned(text.to_owned()))
+                } else {
+                    Token::Word(Cow::Owned(text.to_owned()))
+                }
+            }));
+        }
+        Node::UnsafeText(text) => tokens.push(Token::Word(Cow::Borrowed(&text.text))),
+        Node::Element(element) => {
+            let mut opening_tag = String::new();
+            let _ = Renderer::new(&mut opening_tag, options).opening_tag(element);
+            tokens.push(Token::Word(opening_tag.into()));
+
+            if !element.is_void() {
+                let children = element.children.as_deref().unwrap_or_default();
+
+                if is_preformatted_element(&element.name) {
+                    let mut contents = String::new();
+                    let _ =
+                        Renderer::new(&mut contents, options).element_children(element, children);
+                    tokens.push(Token::Verbatim(Cow::Owned(contents)));
+                } else {
+                    for child in children {
+                        tokenize(child, options, tokens);
+                    }
+                }
+
+                let mut closing_tag = String::new();
+                let _ = Renderer::new(&mut closing_tag, options).closing_tag(element);
+                tokens.push(Token::Word(closing_tag.into()));
+            }
+        }
+        Node::Fragment(fragment) => {
+            for child in &fragment.children {
+                tokenize(child, options, tokens);
+            }
+        }
+        Node::Shared(shared) => tokenize(shared, options, tokens),
+        other => {
+            let mut rendered = String::new();
+            let _ = Renderer::new(&mut rendered, options).node(other);
+
+            if !rendered.is_empty() {
+                tokens.push(Token::Word(rendered.into()));
+            }
+        }
+    }
+}
+
+/// Split text into alternating runs of whitespace and non-whitespace, with
+/// whether each run is whitespace.
+fn split_whitespace(text: &str) -> impl Iterator<Item = (bool, &str)> {
+    let mut rest = text;
+
+    std::iter::from_fn(move || {
+        let is_space = rest.starts_with(|c: char| c.is_ascii_whitespace());
+        let end = rest
+            .find(|c: char| c.is_ascii_whitespace() != is_space)
+            .unwrap_or(rest.len());
+
+        if end == 0 {
+            return None;
+        }
+
+        let (run, remaining) = rest.split_at(end);
+        rest = remaining;
+
+        Some((is_space, run))
+    })
+}
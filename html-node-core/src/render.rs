@@ -0,0 +1,1362 @@
+use std::{
+    array,
+    borrow::Cow,
+    cell::Cell,
+    error::Error,
+    fmt::{self, Display, Formatter, Write},
+    io,
+    iter::{
+        Chain, Cloned, Copied, Empty, Filter, FilterMap, Map, Once, Rev, Skip, SkipWhile, Take,
+        TakeWhile,
+    },
+    slice,
+    vec::IntoIter as VecIntoIter,
+};
+
+use crate::{
+    escape, pretty::is_preformatted_element, Cdata, Comment, Doctype, DoctypeError, Element,
+    Fragment, IntoAttributeValue, Lazy, Node, ProcessingInstruction, RawText, Text, UnsafeText,
+};
+
+/// Options for rendering a [`Node`], used by [`Node::render`] and
+/// [`Node::render_to`].
+///
+/// The default options render nodes exactly like their [`Display`]
+/// implementation.
+///
+/// ```rust
+/// use html_node_core::{
+///     render::{DuplicateAttributes, RenderOptions},
+///     Element, Node,
+/// };
+///
+/// let node = Node::from(Element {
+///     name: "div".into(),
+///     attributes: vec![
+///         ("class".into(), Some("card".into())),
+///         ("id".into(), Some("first".into())),
+///         ("class".into(), Some("wide".into())),
+///         ("id".into(), Some("second".into())),
+///     ],
+///     children: Some(Vec::new()),
+/// });
+///
+/// let options = RenderOptions {
+///     duplicate_attributes: DuplicateAttributes::MergeClass,
+///     ..RenderOptions::default()
+/// };
+///
+/// assert_eq!(
+///     node.render(&options).unwrap(),
+///     r#"<div class="card wide" id="first"></div>"#,
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_copy_implementations)]
+pub struct RenderOptions {
+    /// What to do with elements which have more than one attribute with the
+    /// same name.
+    pub duplicate_attributes: DuplicateAttributes,
+
+    /// Whether to write HTML or XHTML.
+    pub syntax: Syntax,
+
+    /// Whether to make the output as small as possible, without changing how
+    /// it is displayed.
+    ///
+    /// - Runs of whitespace in text are collapsed into a single space, except
+    ///   inside [preformatted elements](crate::pretty::PREFORMATTED_ELEMENTS),
+    ///   and whitespace-only text is removed from elements like `<table>` and
+    ///   `<select>`, where it is never displayed.
+    /// - Comments are removed.
+    /// - When writing [HTML](Syntax::Html), attribute values are left unquoted
+    ///   when that is allowed, empty attribute values are removed, and optional
+    ///   closing tags (like `</li>` and `</p>`) are omitted.
+    pub minify: bool,
+
+    /// Whether to write doctypes in their [canonical
+    /// form](crate::Doctype::normalize), like `<!DOCTYPE html>`.
+    ///
+    /// Doctypes which aren't valid fail with a [`RenderError::InvalidDoctype`].
+    pub normalize_doctypes: bool,
+}
+
+impl RenderOptions {
+    /// The default options, but writing [XHTML](Syntax::Xhtml).
+    ///
+    /// ```rust
+    /// use html_node_core::{el, render::RenderOptions, Node, Text};
+    ///
+    /// let node = Node::from(
+    ///     el::p()
+    ///         .child(Text::from("a & b"))
+    ///         .child(el::br())
+    ///         .child(el::input().attr("type", "checkbox").bool_attr("checked", true)),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     node.render(&RenderOptions::xhtml()).unwrap(),
+    ///     r#"<p xmlns="http://www.w3.org/1999/xhtml">a &amp; b<br /><input type="checkbox" checked="checked" /></p>"#,
+    /// );
+    /// ```
+    #[must_use]
+    pub fn xhtml() -> Self {
+        Self {
+            syntax: Syntax::Xhtml,
+            ..Self::default()
+        }
+    }
+
+    /// The default options, but [minifying](Self::minify) the output.
+    ///
+    /// ```rust
+    /// use html_node_core::{el, render::RenderOptions, Comment, Node, Text};
+    ///
+    /// let node = Node::from(
+    ///     el::ul()
+    ///         .attr("class", "menu")
+    ///         .child(Comment::from("items"))
+    ///         .child(el::li().child(Text::from("  one  ")))
+    ///         .child(el::li().child(Text::from("two"))),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     node.render(&RenderOptions::minified()).unwrap(),
+    ///     "<ul class=menu><li> one <li>two</ul>",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn minified() -> Self {
+        Self {
+            minify: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// The syntax to write nodes with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Syntax {
+    /// HTML syntax, the same as [`Display`].
+    #[default]
+    Html,
+
+    /// XHTML syntax, which is well-formed XML.
+    ///
+    /// - Void elements are self-closing (`<br />`).
+    /// - Attributes without a value are written with their name as their value
+    ///   (`checked="checked"`).
+    /// - Text, including the contents of `<script>` and `<style>` elements, and
+    ///   attribute values are escaped with [`escape::xml_text`] and
+    ///   [`escape::xml_attribute`].
+    /// - The outermost HTML, `<svg>` and `<math>` elements declare their
+    ///   namespace with an `xmlns` attribute (unless they already have one),
+    ///   and elements with `xlink:` attributes declare the `xlink` prefix.
+    ///   Children of an SVG `<foreignObject>` are back in the HTML namespace.
+    /// - Since XML doesn't allow duplicate attributes,
+    ///   [`DuplicateAttributes::KeepAll`] behaves like
+    ///   [`DuplicateAttributes::KeepFirst`].
+    ///
+    /// [`UnsafeText`](crate::UnsafeText), comments and doctypes are written as
+    /// is.
+    Xhtml,
+}
+
+/// What to do with elements which have more than one attribute with the same
+/// name (compared case-insensitively).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DuplicateAttributes {
+    /// Render every attribute, including duplicates.
+    #[default]
+    KeepAll,
+
+    /// Render only the first attribute with each name, which is the one
+    /// browsers use.
+    KeepFirst,
+
+    /// Render only the last attribute with each name.
+    KeepLast,
+
+    /// Merge the values of duplicate `class` attributes into the first one,
+    /// and render only the first of any other duplicate attributes.
+    MergeClass,
+
+    /// Fail with a [`RenderError::DuplicateAttribute`].
+    Error,
+}
+
+/// An error encountered while rendering a [`Node`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RenderError {
+    /// An element has more than one attribute with the same name, and
+    /// [`RenderOptions::duplicate_attributes`] is
+    /// [`DuplicateAttributes::Error`].
+    DuplicateAttribute {
+        /// The name of the element.
+        element: String,
+
+        /// The name of the duplicated attribute.
+        attribute: String,
+    },
+
+    /// A doctype isn't valid, and [`RenderOptions::normalize_doctypes`] is
+    /// set.
+    InvalidDoctype(DoctypeError),
+
+    /// Writing to the underlying [`fmt::Write`] failed.
+    Fmt(fmt::Error),
+
+    /// Writing to the underlying [`io::Write`] failed.
+    Io(io::Error),
+}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateAttribute { element, attribute } => {
+                write!(
+                    f,
+                    "duplicate attribute `{attribute}` on element `{element}`"
+                )
+            }
+            Self::InvalidDoctype(error) => write!(f, "invalid doctype: {error}"),
+            Self::Fmt(_) => write!(f, "failed to write rendered html"),
+            Self::Io(error) => write!(f, "failed to write rendered html: {error}"),
+        }
+    }
+}
+
+impl Error for RenderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::DuplicateAttribute { .. } => None,
+            Self::InvalidDoctype(error) => Some(error),
+            Self::Fmt(error) => Some(error),
+            Self::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for RenderError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// A value which can be written as HTML.
+///
+/// Implementations write nodes and text to an [`Output`], which renders them
+/// with the [`RenderOptions`] in use. Text written to an [`Output`] (including
+/// with [`write!`]) is escaped.
+///
+/// Wrapping a value in [`Lazy`] (or using [`Node::lazy`] and
+/// [`Node::stream`]) puts it in a tree, to be rendered when the tree is.
+///
+/// ```rust
+/// use std::fmt::{self, Write};
+///
+/// use html_node_core::{
+///     el,
+///     render::{Output, Render},
+///     Lazy, Node,
+/// };
+///
+/// struct Price(u32);
+///
+/// impl Render for Price {
+///     fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+///         output.node(&el::data().attr("value", self.0.to_string()).into())?;
+///         write!(output, "${}.{:02} <excl. tax>", self.0 / 100, self.0 % 100)
+///     }
+/// }
+///
+/// assert_eq!(
+///     Node::Lazy(Lazy::new(Price(1250))).to_string(),
+///     r#"<data value="1250"></data>$12.50 &lt;excl. tax>"#,
+/// );
+/// ```
+pub trait Render {
+    /// Write the value to `output`.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error returned by `output`.
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result;
+
+    /// Convert the value into a [`Node`], which is how the `html!` macro
+    /// handles values returned from braced blocks.
+    ///
+    /// By default, the value is rendered into the nodes it writes, giving
+    /// either the only node written or a [`Node::Fragment`] of all of them.
+    /// Text written in pieces becomes a single [`Text`] node. If rendering
+    /// returns an error, it is ignored, and the nodes written before it are
+    /// kept.
+    fn into_node(self) -> Node
+    where
+        Self: Sized,
+    {
+        let mut nodes = Output::collect(&self);
+
+        if nodes.len() == 1 {
+            return nodes.remove(0);
+        }
+
+        Node::Fragment(Fragment { children: nodes })
+    }
+}
+
+impl Render for Node {
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        output.node(self)
+    }
+
+    fn into_node(self) -> Node {
+        self
+    }
+}
+
+macro_rules! impl_render_for_nodes {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Render for $ty {
+                fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+                    output.node(&Node::from(self.clone()))
+                }
+
+                fn into_node(self) -> Node {
+                    Node::from(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_render_for_nodes!(
+    Cdata,
+    Comment,
+    Doctype,
+    Element,
+    Fragment,
+    ProcessingInstruction,
+    RawText,
+    Text,
+    UnsafeText,
+);
+
+impl Render for Lazy {
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        output.node(&Node::Lazy(self.clone()))
+    }
+
+    fn into_node(self) -> Node {
+        Node::Lazy(self)
+    }
+}
+
+impl Render for str {
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        output.text(self)
+    }
+}
+
+impl Render for String {
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        output.text(self)
+    }
+
+    fn into_node(self) -> Node {
+        Node::Text(Text { text: self })
+    }
+}
+
+impl Render for Cow<'_, str> {
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        output.text(self)
+    }
+}
+
+impl Render for char {
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        output.text(self.encode_utf8(&mut [0; 4]))
+    }
+}
+
+macro_rules! impl_render_for_numbers {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Render for $ty {
+                fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+                    write!(output, "{self}")
+                }
+            }
+        )*
+    };
+}
+
+with_number_types!(impl_render_for_numbers!);
+
+impl<T: Render + ?Sized> Render for &T {
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        (**self).render_html(output)
+    }
+}
+
+impl<T: Render + ?Sized> Render for Box<T> {
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        (**self).render_html(output)
+    }
+}
+
+impl<T: Render> Render for Option<T> {
+    /// Render the value if there is one, or nothing otherwise.
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        self.as_ref()
+            .map_or(Ok(()), |value| value.render_html(output))
+    }
+
+    fn into_node(self) -> Node {
+        self.map_or(Node::EMPTY, Render::into_node)
+    }
+}
+
+impl<T: Render, E: Render> Render for Result<T, E> {
+    /// Render whichever value there is.
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        match self {
+            Ok(value) => value.render_html(output),
+            Err(error) => error.render_html(output),
+        }
+    }
+
+    fn into_node(self) -> Node {
+        match self {
+            Ok(value) => value.into_node(),
+            Err(error) => error.into_node(),
+        }
+    }
+}
+
+impl<T: Render> Render for [T] {
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        self.iter().try_for_each(|value| value.render_html(output))
+    }
+}
+
+impl<T: Render, const N: usize> Render for [T; N] {
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        self.as_slice().render_html(output)
+    }
+
+    fn into_node(self) -> Node {
+        Node::Fragment(self.into_iter().map(Render::into_node).collect())
+    }
+}
+
+impl<T: Render> Render for Vec<T> {
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        self.as_slice().render_html(output)
+    }
+
+    fn into_node(self) -> Node {
+        Node::Fragment(self.into_iter().map(Render::into_node).collect())
+    }
+}
+
+// iterators can only be rendered through a shared reference by cloning them,
+// but converting them into nodes (like the `html!` macro does) consumes them
+// directly.
+macro_rules! impl_render_for_iterators {
+    ($($ty:ident<$($param:tt),*>),* $(,)?) => {
+        $(
+            impl<$($param),*> Render for $ty<$($param),*>
+            where
+                Self: Iterator + Clone,
+                <Self as Iterator>::Item: Render,
+            {
+                fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+                    self.clone().try_for_each(|value| value.render_html(output))
+                }
+
+                fn into_node(self) -> Node {
+                    Node::Fragment(self.map(Render::into_node).collect())
+                }
+            }
+        )*
+    };
+}
+
+impl_render_for_iterators!(
+    Chain<A, B>,
+    Cloned<I>,
+    Copied<I>,
+    Empty<T>,
+    Filter<I, P>,
+    FilterMap<I, F>,
+    Map<I, F>,
+    Once<T>,
+    Rev<I>,
+    Skip<I>,
+    SkipWhile<I, P>,
+    Take<I>,
+    TakeWhile<I, P>,
+    VecIntoIter<T>,
+);
+
+impl<T: Render> Render for slice::Iter<'_, T> {
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        self.as_slice().render_html(output)
+    }
+
+    fn into_node(self) -> Node {
+        Node::Fragment(self.map(Render::into_node).collect())
+    }
+}
+
+impl<T: Render, const N: usize> Render for array::IntoIter<T, N> {
+    fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+        self.as_slice().render_html(output)
+    }
+
+    fn into_node(self) -> Node {
+        Node::Fragment(self.map(Render::into_node).collect())
+    }
+}
+
+/// A value from a braced block in the `html!` macro, which is converted into a
+/// [`Node`] through [`Render`] if it implements it, or through [`Into<Node>`]
+/// otherwise.
+///
+/// The macro calls `(&Block::new(value)).take_node()`, and method resolution
+/// tries [`RenderBlock`] (implemented for `Block<T>`) before [`IntoNodeBlock`]
+/// (implemented for `&Block<T>`, so it needs another reference).
+#[doc(hidden)]
+pub struct Block<T>(Cell<Option<T>>);
+
+impl<T> Block<T> {
+    #[doc(hidden)]
+    pub const fn new(value: T) -> Self {
+        Self(Cell::new(Some(value)))
+    }
+
+    pub(crate) fn take(&self) -> T {
+        self.0
+            .take()
+            .expect("a block's value should only be converted once")
+    }
+}
+
+impl<T> fmt::Debug for Block<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Block").finish_non_exhaustive()
+    }
+}
+
+#[doc(hidden)]
+pub trait RenderBlock {
+    fn take_node(&self) -> Node;
+}
+
+impl<T: Render> RenderBlock for Block<T> {
+    fn take_node(&self) -> Node {
+        self.take().into_node()
+    }
+}
+
+#[doc(hidden)]
+pub trait IntoNodeBlock {
+    fn take_node(&self) -> Node;
+}
+
+impl<T: Into<Node>> IntoNodeBlock for &Block<T> {
+    fn take_node(&self) -> Node {
+        self.take().into()
+    }
+}
+
+/// Like [`RenderBlock`] and [`IntoNodeBlock`], but for attribute values,
+/// which are converted through [`IntoAttributeValue`] if they implement it,
+/// or through [`ToString`] otherwise.
+#[doc(hidden)]
+pub trait AttributeValueBlock {
+    fn take_attribute_value(&self, name: &str) -> Option<Option<String>>;
+}
+
+impl<T: IntoAttributeValue> AttributeValueBlock for Block<T> {
+    fn take_attribute_value(&self, name: &str) -> Option<Option<String>> {
+        self.take().into_attribute_value_for(name)
+    }
+}
+
+#[doc(hidden)]
+pub trait ToStringAttributeValueBlock {
+    fn take_attribute_value(&self, name: &str) -> Option<Option<String>>;
+}
+
+impl<T: ToString> ToStringAttributeValueBlock for &Block<T> {
+    fn take_attribute_value(&self, _name: &str) -> Option<Option<String>> {
+        Some(Some(self.take().to_string()))
+    }
+}
+
+/// Where a [`Render`] implementation writes its nodes and text.
+///
+/// Implements [`fmt::Write`], escaping everything written as text.
+pub struct Output<'r> {
+    sink: &'r mut dyn Sink,
+}
+
+impl Output<'_> {
+    /// Write a node.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the underlying writer fails (or
+    /// rendering the node fails).
+    pub fn node(&mut self, node: &Node) -> fmt::Result {
+        self.sink.node(node)
+    }
+
+    /// Write text, escaping it like a [`Text`] node.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the underlying writer fails.
+    pub fn text(&mut self, text: &str) -> fmt::Result {
+        self.sink.text(text)
+    }
+
+    /// Write any other value which can be rendered.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error returned by `value`.
+    pub fn render(&mut self, value: &(impl Render + ?Sized)) -> fmt::Result {
+        value.render_html(self)
+    }
+
+    /// Render a value into the nodes it writes.
+    pub(crate) fn collect(value: &(impl Render + ?Sized)) -> Vec<Node> {
+        struct Collector(Vec<Node>);
+
+        impl Sink for Collector {
+            fn node(&mut self, node: &Node) -> fmt::Result {
+                self.0.push(node.clone());
+                Ok(())
+            }
+
+            fn text(&mut self, text: &str) -> fmt::Result {
+                // text is often written in pieces (like with `write!`), which
+                // should still be one node.
+                if let Some(Node::Text(last)) = self.0.last_mut() {
+                    last.text.push_str(text);
+                } else {
+                    self.0.push(Node::Text(Text::from(text)));
+                }
+
+                Ok(())
+            }
+        }
+
+        let mut collector = Collector(Vec::new());
+        // collecting never fails, so an error can only come from the value
+        // itself, and everything it wrote before that is kept.
+        let _ = value.render_html(&mut Output {
+            sink: &mut collector,
+        });
+
+        collector.0
+    }
+}
+
+impl Write for Output<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.text(s)
+    }
+}
+
+impl fmt::Debug for Output<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Output").finish_non_exhaustive()
+    }
+}
+
+/// Something an [`Output`] writes to.
+trait Sink {
+    fn node(&mut self, node: &Node) -> fmt::Result;
+    fn text(&mut self, text: &str) -> fmt::Result;
+}
+
+impl Sink for Renderer<'_, '_> {
+    fn node(&mut self, node: &Node) -> fmt::Result {
+        Renderer::node(self, node)
+    }
+
+    fn text(&mut self, text: &str) -> fmt::Result {
+        Renderer::text(self, text)
+    }
+}
+
+/// Render a node to a [`String`].
+pub(crate) fn render(node: &Node, options: &RenderOptions) -> Result<String, RenderError> {
+    let mut html = String::new();
+
+    let mut renderer = Renderer::new(&mut html, options);
+    let result = renderer.node(node);
+    renderer.finish(result)?;
+
+    Ok(html)
+}
+
+/// Render a node to an [`io::Write`] sink.
+pub(crate) fn render_to<W: io::Write + ?Sized>(
+    node: &Node,
+    writer: &mut W,
+    options: &RenderOptions,
+) -> Result<(), RenderError> {
+    /// Adapts an [`io::Write`] to a [`fmt::Write`], keeping the first error.
+    struct Adapter<'w, W: ?Sized> {
+        writer: &'w mut W,
+        error: Option<io::Error>,
+    }
+
+    impl<W: io::Write + ?Sized> Write for Adapter<'_, W> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.writer.write_all(s.as_bytes()).map_err(|error| {
+                self.error = Some(error);
+                fmt::Error
+            })
+        }
+    }
+
+    let mut adapter = Adapter {
+        writer,
+        error: None,
+    };
+
+    let mut renderer = Renderer::new(&mut adapter, options);
+    let result = renderer.node(node);
+    let result = renderer.finish(result);
+
+    adapter
+        .error
+        .map_or(result, |error| Err(RenderError::Io(error)))
+}
+
+/// Walks a [`Node`] tree, writing it out compactly according to a
+/// [`RenderOptions`].
+///
+/// This is what the (non-alternate) [`Display`] implementations use, with the
+/// default options.
+pub(crate) struct Renderer<'a, 'o> {
+    f: &'a mut dyn Write,
+    options: &'o RenderOptions,
+    error: Option<RenderError>,
+    scope: Scope,
+    preformatted: bool,
+
+    /// Whether the last thing written was text ending in whitespace, so
+    /// whitespace at the start of the next text can be dropped when minifying.
+    after_whitespace: bool,
+}
+
+/// The XML namespaces of the element being written.
+#[derive(Clone, Copy, Default)]
+struct Scope {
+    /// The default namespace which has been declared, if any.
+    declared: Option<Namespace>,
+
+    /// The namespace of child elements which aren't `<svg>` or `<math>`.
+    children: Namespace,
+
+    /// Whether the `xlink` prefix has been declared.
+    xlink: bool,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum Namespace {
+    #[default]
+    Html,
+    Svg,
+    MathMl,
+}
+
+impl Namespace {
+    const fn uri(self) -> &'static str {
+        match self {
+            Self::Html => "http://www.w3.org/1999/xhtml",
+            Self::Svg => "http://www.w3.org/2000/svg",
+            Self::MathMl => "http://www.w3.org/1998/Math/MathML",
+        }
+    }
+}
+
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+impl<'a, 'o> Renderer<'a, 'o> {
+    pub(crate) fn new(f: &'a mut dyn Write, options: &'o RenderOptions) -> Self {
+        Self {
+            f,
+            options,
+            error: None,
+            scope: Scope::default(),
+            preformatted: false,
+            after_whitespace: false,
+        }
+    }
+
+    fn is_xhtml(&self) -> bool {
+        self.options.syntax == Syntax::Xhtml
+    }
+
+    /// Turn the result of rendering into a [`RenderError`], if it failed.
+    pub(crate) fn finish(self, result: fmt::Result) -> Result<(), RenderError> {
+        result.map_err(|error| self.error.unwrap_or(RenderError::Fmt(error)))
+    }
+
+    /// Write any node.
+    pub(crate) fn node(&mut self, node: &Node) -> fmt::Result {
+        if !matches!(
+            node,
+            Node::Text(_) | Node::Fragment(_) | Node::Shared(_) | Node::Lazy(_) | Node::Comment(_)
+        ) {
+            self.after_whitespace = false;
+        }
+
+        match node {
+            Node::Element(element) => self.element(element),
+            Node::Fragment(fragment) => self.children(&fragment.children),
+            Node::Shared(shared) => self.node(shared),
+            Node::Lazy(lazy) => self.lazy(lazy),
+            Node::Comment(_) if self.options.minify => Ok(()),
+            Node::Doctype(doctype) if self.options.normalize_doctypes => {
+                match doctype.normalize() {
+                    Ok(doctype) => write!(self.f, "{doctype}"),
+                    Err(error) => {
+                        self.error = Some(RenderError::InvalidDoctype(error));
+                        Err(fmt::Error)
+                    }
+                }
+            }
+            Node::Text(text) => self.text(&text.text),
+            Node::RawText(raw_text) if self.is_xhtml() => {
+                self.f.write_str(&escape::xml_text(&raw_text.text))
+            }
+            other => write!(self.f, "{other}"),
+        }
+    }
+
+    /// Write the contents of a lazy node.
+    pub(crate) fn lazy(&mut self, lazy: &Lazy) -> fmt::Result {
+        lazy.as_render().render_html(&mut Output { sink: self })
+    }
+
+    /// Write text, escaping it.
+    pub(crate) fn text(&mut self, text: &str) -> fmt::Result {
+        let text = if self.options.minify && !self.preformatted {
+            collapse_whitespace(text, &mut self.after_whitespace)
+        } else {
+            self.after_whitespace = false;
+            Cow::Borrowed(text)
+        };
+
+        if self.is_xhtml() {
+            self.f.write_str(&escape::xml_text(&text))
+        } else {
+            self.f.write_str(&html_escape::encode_text_minimal(&text))
+        }
+    }
+
+    /// Write a list of nodes.
+    pub(crate) fn children(&mut self, children: &[Node]) -> fmt::Result {
+        self.child_nodes(None, children)
+    }
+
+    /// Write a list of nodes, which are the children of `parent` if it is
+    /// known.
+    fn child_nodes(&mut self, parent: Option<&Element>, children: &[Node]) -> fmt::Result {
+        if !self.options.minify {
+            for child in children {
+                self.node(child)?;
+            }
+
+            return Ok(());
+        }
+
+        // closing tags can only be omitted depending on the node which comes
+        // next, so flatten fragments and drop the nodes which won't be written
+        // first.
+        let mut nodes = Vec::new();
+        flatten(children, &mut nodes);
+
+        if !self.preformatted && parent.is_some_and(|parent| ignores_whitespace(&parent.name)) {
+            nodes.retain(|node| !matches!(node, Node::Text(text) if is_whitespace(&text.text)));
+        }
+
+        for (i, node) in nodes.iter().enumerate() {
+            match node {
+                Node::Element(element) => {
+                    let omit_closing_tag = !self.is_xhtml()
+                        && can_omit_closing_tag(element, parent, nodes.get(i + 1).copied());
+
+                    self.write_element(element, omit_closing_tag)?;
+                }
+                other => self.node(other)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write an element, with its children.
+    ///
+    /// Void elements are written without their children or a closing tag.
+    pub(crate) fn element(&mut self, element: &Element) -> fmt::Result {
+        self.write_element(element, false)
+    }
+
+    fn write_element(&mut self, element: &Element, omit_closing_tag: bool) -> fmt::Result {
+        let xhtml = self.is_xhtml();
+        let outer = (self.scope, self.preformatted);
+        self.after_whitespace = false;
+
+        let declarations = if xhtml {
+            self.enter(element)
+        } else {
+            Vec::new()
+        };
+
+        self.write_opening_tag(element, &declarations, xhtml && element.is_void())?;
+
+        if !element.is_void() {
+            self.preformatted |= is_preformatted_element(&element.name);
+            self.element_children(element, element.children.as_deref().unwrap_or_default())?;
+
+            if !omit_closing_tag {
+                self.closing_tag(element)?;
+            }
+        }
+
+        (self.scope, self.preformatted) = outer;
+        self.after_whitespace = false;
+        Ok(())
+    }
+
+    /// Enter the namespace scope of an element, returning the namespace
+    /// declarations it needs.
+    fn enter(&mut self, element: &Element) -> Vec<(&'static str, &'static str)> {
+        let namespace = if element.name.eq_ignore_ascii_case("svg") {
+            Namespace::Svg
+        } else if element.name.eq_ignore_ascii_case("math") {
+            Namespace::MathMl
+        } else {
+            self.scope.children
+        };
+
+        let mut declarations = Vec::new();
+
+        if self.scope.declared != Some(namespace) && !element.has_attr("xmlns") {
+            declarations.push(("xmlns", namespace.uri()));
+        }
+
+        let has_xlink_attribute = element.attributes.iter().any(|(key, _)| {
+            key.get(..6)
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case("xlink:"))
+        });
+
+        if has_xlink_attribute && !self.scope.xlink && !element.has_attr("xmlns:xlink") {
+            declarations.push(("xmlns:xlink", XLINK_NAMESPACE));
+        }
+
+        self.scope = Scope {
+            declared: Some(namespace),
+            children: if namespace == Namespace::Svg
+                && element.name.eq_ignore_ascii_case("foreignObject")
+            {
+                Namespace::Html
+            } else {
+                namespace
+            },
+            xlink: self.scope.xlink || has_xlink_attribute,
+        };
+
+        declarations
+    }
+
+    /// Write the opening tag of an element, including its attributes.
+    pub(crate) fn opening_tag(&mut self, element: &Element) -> fmt::Result {
+        self.write_opening_tag(element, &[], false)
+    }
+
+    fn write_opening_tag(
+        &mut self,
+        element: &Element,
+        declarations: &[(&str, &str)],
+        self_closing: bool,
+    ) -> fmt::Result {
+        let xhtml = self.is_xhtml();
+        let unquote = self.options.minify && !xhtml;
+
+        let policy = match self.options.duplicate_attributes {
+            DuplicateAttributes::KeepAll if xhtml => DuplicateAttributes::KeepFirst,
+            policy => policy,
+        };
+
+        let attributes = match deduplicate(element, policy) {
+            Ok(attributes) => attributes,
+            Err(error) => {
+                self.error = Some(error);
+                return Err(fmt::Error);
+            }
+        };
+
+        write!(self.f, "<{}", element.name)?;
+
+        for (key, uri) in declarations {
+            write!(self.f, r#" {key}="{uri}""#)?;
+        }
+
+        for (key, value) in attributes.iter() {
+            write!(self.f, " {key}")?;
+
+            let value = match value {
+                Some(value) if escape::is_url_attribute(key) => escape::url(value),
+                Some(value) => value,
+                None if xhtml => key,
+                None => continue,
+            };
+
+            if xhtml {
+                write!(self.f, r#"="{}""#, escape::xml_attribute(value))?;
+            } else if unquote && value.is_empty() {
+                // an attribute with no value has an empty value.
+            } else if unquote && can_unquote(value) {
+                // unquoted values can't contain `<`, so only `&` needs escaping.
+                write!(self.f, "={}", html_escape::encode_text_minimal(value))?;
+            } else {
+                let encoded_value = html_escape::encode_double_quoted_attribute(value);
+                write!(self.f, r#"="{encoded_value}""#)?;
+            }
+        }
+
+        if self_closing {
+            write!(self.f, " />")
+        } else {
+            write!(self.f, ">")
+        }
+    }
+
+    /// Write the closing tag of an element.
+    pub(crate) fn closing_tag(&mut self, element: &Element) -> fmt::Result {
+        write!(self.f, "</{}>", element.name)
+    }
+
+    /// Write the children of an element.
+    ///
+    /// The contents of `<script>` and `<style>` elements are escaped for their
+    /// context (see [`escape::script`] and [`escape::style`]) instead of being
+    /// HTML-escaped, unless writing XHTML.
+    pub(crate) fn element_children(&mut self, element: &Element, children: &[Node]) -> fmt::Result {
+        if self.is_xhtml() {
+            return self.child_nodes(Some(element), children);
+        }
+
+        let escape_raw_text: fn(&str) -> Cow<'_, str> =
+            if element.name.eq_ignore_ascii_case("script") {
+                escape::script
+            } else if element.name.eq_ignore_ascii_case("style") {
+                escape::style
+            } else {
+                return self.child_nodes(Some(element), children);
+            };
+
+        self.raw_text(children, escape_raw_text)
+    }
+
+    fn raw_text(
+        &mut self,
+        children: &[Node],
+        escape_raw_text: fn(&str) -> Cow<'_, str>,
+    ) -> fmt::Result {
+        // adjacent text is escaped all at once, as something like `</script`
+        // could be split across several nodes.
+        let mut text = String::new();
+        self.raw_text_nodes(children, escape_raw_text, &mut text)?;
+        self.f.write_str(&escape_raw_text(&text))
+    }
+
+    fn raw_text_nodes(
+        &mut self,
+        children: &[Node],
+        escape_raw_text: fn(&str) -> Cow<'_, str>,
+        text: &mut String,
+    ) -> fmt::Result {
+        for child in children {
+            match child {
+                Node::Text(Text { text: child }) | Node::RawText(RawText { text: child }) => {
+                    text.push_str(child);
+                }
+                Node::Fragment(fragment) => {
+                    self.raw_text_nodes(&fragment.children, escape_raw_text, text)?;
+                }
+                Node::Shared(shared) => {
+                    self.raw_text_nodes(std::slice::from_ref(&**shared), escape_raw_text, text)?;
+                }
+                Node::Lazy(lazy) => {
+                    let nodes = Output::collect(lazy.as_render());
+                    self.raw_text_nodes(&nodes, escape_raw_text, text)?;
+                }
+                other => {
+                    self.f.write_str(&escape_raw_text(text))?;
+                    text.clear();
+                    self.node(other)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Collect the nodes in a list which will be written, flattening fragments (and
+/// shared nodes) and dropping comments.
+fn flatten<'n>(children: &'n [Node], nodes: &mut Vec<&'n Node>) {
+    for child in children {
+        match child {
+            Node::Fragment(fragment) => flatten(&fragment.children, nodes),
+            Node::Shared(shared) => flatten(std::slice::from_ref(&**shared), nodes),
+            Node::Comment(_) => {}
+            other => nodes.push(other),
+        }
+    }
+}
+
+/// Whether the string is empty or only ASCII whitespace.
+fn is_whitespace(text: &str) -> bool {
+    text.bytes().all(|byte| byte.is_ascii_whitespace())
+}
+
+/// Collapse each run of ASCII whitespace into a single space.
+///
+/// `after_whitespace` is whether the text written just before ended in
+/// whitespace, in which case whitespace at the start of `text` is dropped. It
+/// is updated for the next text.
+fn collapse_whitespace<'t>(text: &'t str, after_whitespace: &mut bool) -> Cow<'t, str> {
+    let starts_with_whitespace = text.starts_with(|c: char| c.is_ascii_whitespace());
+    let needs_collapsing = (*after_whitespace && starts_with_whitespace)
+        || text
+            .as_bytes()
+            .windows(2)
+            .any(|pair| pair[0].is_ascii_whitespace() && pair[1].is_ascii_whitespace())
+        || text
+            .bytes()
+            .any(|byte| byte.is_ascii_whitespace() && byte != b' ');
+
+    let mut in_whitespace = *after_whitespace;
+
+    if !text.is_empty() {
+        *after_whitespace = text.ends_with(|c: char| c.is_ascii_whitespace());
+    }
+
+    if !needs_collapsing {
+        return Cow::Borrowed(text);
+    }
+
+    let mut collapsed = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !in_whitespace {
+                collapsed.push(' ');
+            }
+
+            in_whitespace = true;
+        } else {
+            collapsed.push(c);
+            in_whitespace = false;
+        }
+    }
+
+    Cow::Owned(collapsed)
+}
+
+/// Whether whitespace-only text inside the element with the given name is
+/// never displayed.
+fn ignores_whitespace(name: &str) -> bool {
+    [
+        "colgroup", "datalist", "head", "html", "optgroup", "select", "table", "tbody", "tfoot",
+        "thead", "tr",
+    ]
+    .iter()
+    .any(|ignoring| ignoring.eq_ignore_ascii_case(name))
+}
+
+/// Whether an attribute value can be written without quotes.
+fn can_unquote(value: &str) -> bool {
+    !value.is_empty()
+        && !value
+            .chars()
+            .any(|c| c.is_ascii_whitespace() || matches!(c, '"' | '\'' | '=' | '<' | '>' | '`'))
+}
+
+/// Whether the closing tag of an element can be omitted, given its parent (if
+/// known) and the node which comes after it.
+///
+/// This follows the [optional tags] section of the HTML standard, except that
+/// elements with an unknown parent keep their closing tag unless they are the
+/// last node.
+///
+/// [optional tags]: https://html.spec.whatwg.org/multipage/syntax.html#optional-tags
+fn can_omit_closing_tag(element: &Element, parent: Option<&Element>, next: Option<&Node>) -> bool {
+    const P_CLOSERS: &[&str] = &[
+        "address",
+        "article",
+        "aside",
+        "blockquote",
+        "details",
+        "dialog",
+        "div",
+        "dl",
+        "fieldset",
+        "figcaption",
+        "figure",
+        "footer",
+        "form",
+        "h1",
+        "h2",
+        "h3",
+        "h4",
+        "h5",
+        "h6",
+        "header",
+        "hgroup",
+        "hr",
+        "main",
+        "menu",
+        "nav",
+        "ol",
+        "p",
+        "pre",
+        "search",
+        "section",
+        "table",
+        "ul",
+    ];
+
+    let next_is = |names: &[&str]| matches!(next, Some(Node::Element(next)) if names.iter().any(|name| name.eq_ignore_ascii_case(&next.name)));
+    let is_last = next.is_none() && parent.is_some();
+    let next_is_element_or_last = matches!(next, Some(Node::Element(_))) || is_last;
+
+    match element.name.to_ascii_lowercase().as_str() {
+        "html" | "body" => next.is_none(),
+        "head" => matches!(next, Some(Node::Element(_))),
+        "li" => next_is(&["li"]) || is_last,
+        "dt" => next_is(&["dt", "dd"]),
+        "dd" => next_is(&["dt", "dd"]) || is_last,
+        "p" => {
+            next_is(P_CLOSERS)
+                || (is_last
+                    && parent.is_some_and(|parent| {
+                        // autonomous custom elements (with a `-` in their name)
+                        // can't close a `<p>` either.
+                        !parent.name.contains('-')
+                            && !["a", "audio", "del", "ins", "map", "noscript", "video"]
+                                .iter()
+                                .any(|name| name.eq_ignore_ascii_case(&parent.name))
+                    }))
+        }
+        "rt" | "rp" => next_is(&["rt", "rp"]) || is_last,
+        "optgroup" => next_is(&["optgroup", "hr"]) || is_last,
+        "option" => next_is(&["option", "optgroup", "hr"]) || is_last,
+        "caption" | "colgroup" => next_is_element_or_last,
+        "thead" => next_is(&["tbody", "tfoot"]),
+        "tbody" => next_is(&["tbody", "tfoot"]) || is_last,
+        "tfoot" => is_last,
+        "tr" => next_is(&["tr"]) || is_last,
+        "td" | "th" => next_is(&["td", "th"]) || is_last,
+        _ => false,
+    }
+}
+
+type Attributes = [(String, Option<String>)];
+
+/// Apply a [`DuplicateAttributes`] policy to the attributes of an element.
+fn deduplicate(
+    element: &Element,
+    policy: DuplicateAttributes,
+) -> Result<Cow<'_, Attributes>, RenderError> {
+    let attributes = &element.attributes;
+
+    let is_duplicate = |i: usize| {
+        attributes[..i]
+            .iter()
+            .any(|(key, _)| key.eq_ignore_ascii_case(&attributes[i].0))
+    };
+
+    let Some(first_duplicate) = (0..attributes.len()).find(|&i| is_duplicate(i)) else {
+        return Ok(Cow::Borrowed(attributes));
+    };
+
+    let mut deduplicated = Vec::<(String, Option<String>)>::with_capacity(attributes.len());
+
+    let position = |deduplicated: &Attributes, key: &str| {
+        deduplicated
+            .iter()
+            .position(|(other, _)| other.eq_ignore_ascii_case(key))
+    };
+
+    match policy {
+        DuplicateAttributes::KeepAll => return Ok(Cow::Borrowed(attributes)),
+        DuplicateAttributes::Error => {
+            return Err(RenderError::DuplicateAttribute {
+                element: element.name.clone(),
+                attribute: attributes[first_duplicate].0.clone(),
+            })
+        }
+        DuplicateAttributes::KeepFirst => {
+            for (key, value) in attributes {
+                if position(&deduplicated, key).is_none() {
+                    deduplicated.push((key.clone(), value.clone()));
+                }
+            }
+        }
+        DuplicateAttributes::KeepLast => {
+            for (key, value) in attributes.iter().rev() {
+                if position(&deduplicated, key).is_none() {
+                    deduplicated.push((key.clone(), value.clone()));
+                }
+            }
+
+            deduplicated.reverse();
+        }
+        DuplicateAttributes::MergeClass => {
+            for (key, value) in attributes {
+                match position(&deduplicated, key) {
+                    None => deduplicated.push((key.clone(), value.clone())),
+                    Some(i) if key.eq_ignore_ascii_case("class") => {
+                        let classes = deduplicated[i].1.get_or_insert_with(String::new);
+
+                        for class in value
+                            .iter()
+                            .flat_map(|value| value.split_ascii_whitespace())
+                        {
+                            if !classes.split_ascii_whitespace().any(|other| other == class) {
+                                if !classes.is_empty() {
+                                    classes.push(' ');
+                                }
+
+                                classes.push_str(class);
+                            }
+                        }
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    Ok(Cow::Owned(deduplicated))
+}
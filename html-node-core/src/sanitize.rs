@@ -0,0 +1,302 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::{escape, parse::parse_lenient, Element, Fragment, Node, Text};
+
+/// An allow-list of the elements, attributes and URL schemes which are kept
+/// when sanitizing untrusted HTML.
+///
+/// Anything not allowed by the policy is removed:
+///
+/// - Elements which aren't allowed are replaced by their (sanitized) children,
+///   unless they are in [`Policy::removed_elements`], in which case their
+///   children are removed too.
+/// - Attributes which aren't allowed are removed. Event handler attributes
+///   (`on*`) are always removed, even if the policy allows them.
+/// - [URL attributes](Policy::url_attributes) whose value has a scheme not in
+///   [`Policy::url_schemes`] (like `javascript:`) are removed.
+/// - Comments and doctypes are removed, unless [`Policy::allow_comments`] is
+///   set.
+/// - [`Node::UnsafeText`] is parsed and sanitized like any other untrusted
+///   HTML.
+///
+/// ```rust
+/// use html_node_core::sanitize::Policy;
+///
+/// let untrusted = r#"<p onclick="steal()">hi <a href="javascript:steal()">there</a><script>steal()</script></p>"#;
+///
+/// let sanitized = Policy::basic().sanitize_html(untrusted);
+///
+/// assert_eq!(sanitized.to_string(), "<p>hi <a>there</a></p>");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Policy {
+    /// The names of the elements which are kept.
+    pub elements: HashSet<String>,
+
+    /// The names of the attributes which are kept, by the name of the element
+    /// they are on.
+    ///
+    /// Attributes under `"*"` are kept on every allowed element.
+    pub attributes: HashMap<String, HashSet<String>>,
+
+    /// The names of the attributes whose values are URLs, which are checked
+    /// against [`Policy::url_schemes`].
+    pub url_attributes: HashSet<String>,
+
+    /// The URL schemes which are allowed in [URL
+    /// attributes](Policy::url_attributes).
+    ///
+    /// Relative URLs (with no scheme) are always allowed.
+    pub url_schemes: HashSet<String>,
+
+    /// The names of the elements which are removed along with all of their
+    /// children, instead of being replaced by them.
+    pub removed_elements: HashSet<String>,
+
+    /// Whether comments are kept.
+    pub allow_comments: bool,
+}
+
+impl Policy {
+    /// A policy which keeps nothing but text.
+    ///
+    /// Elements in [`Policy::removed_elements`] (like `script` and `style`)
+    /// are still removed along with their contents.
+    #[must_use]
+    pub fn text_only() -> Self {
+        Self {
+            url_attributes: [
+                "action",
+                "background",
+                "cite",
+                "formaction",
+                "href",
+                "longdesc",
+                "poster",
+                "src",
+                "xlink:href",
+            ]
+            .map(String::from)
+            .into(),
+            url_schemes: ["http", "https", "mailto"].map(String::from).into(),
+            removed_elements: [
+                "embed", "iframe", "math", "noscript", "object", "script", "style", "svg",
+                "template", "textarea", "title",
+            ]
+            .map(String::from)
+            .into(),
+            ..Self::default()
+        }
+    }
+
+    /// A policy which keeps basic inline formatting and links.
+    #[must_use]
+    pub fn basic() -> Self {
+        Self::text_only()
+            .allow_elements([
+                "a", "b", "br", "code", "em", "i", "p", "s", "strong", "sub", "sup", "u",
+            ])
+            .allow_attributes("a", ["href", "title"])
+    }
+
+    /// A policy which keeps the formatting usually produced by rich text
+    /// editors and markdown: headings, lists, quotes, code blocks, tables and
+    /// images, on top of [`Policy::basic`].
+    #[must_use]
+    pub fn rich_text() -> Self {
+        Self::basic()
+            .allow_elements([
+                "abbr",
+                "blockquote",
+                "caption",
+                "cite",
+                "dd",
+                "del",
+                "div",
+                "dl",
+                "dt",
+                "figcaption",
+                "figure",
+                "h1",
+                "h2",
+                "h3",
+                "h4",
+                "h5",
+                "h6",
+                "hr",
+                "img",
+                "ins",
+                "kbd",
+                "li",
+                "mark",
+                "ol",
+                "pre",
+                "q",
+                "small",
+                "span",
+                "table",
+                "tbody",
+                "td",
+                "tfoot",
+                "th",
+                "thead",
+                "tr",
+                "ul",
+            ])
+            .allow_attributes("*", ["lang", "title"])
+            .allow_attributes("abbr", ["title"])
+            .allow_attributes("blockquote", ["cite"])
+            .allow_attributes("img", ["alt", "height", "src", "width"])
+            .allow_attributes("ol", ["reversed", "start"])
+            .allow_attributes("q", ["cite"])
+            .allow_attributes("td", ["colspan", "rowspan"])
+            .allow_attributes("th", ["colspan", "rowspan", "scope"])
+    }
+
+    /// Allow the given elements.
+    #[must_use]
+    pub fn allow_elements<I, S>(mut self, elements: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.elements.extend(elements.into_iter().map(Into::into));
+        self
+    }
+
+    /// Allow the given attributes on the element with the given name, or on
+    /// every element if the name is `"*"`.
+    #[must_use]
+    pub fn allow_attributes<I, S>(mut self, element: &str, attributes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.attributes
+            .entry(element.into())
+            .or_default()
+            .extend(attributes.into_iter().map(Into::into));
+        self
+    }
+
+    /// Allow the given URL schemes.
+    #[must_use]
+    pub fn allow_url_schemes<I, S>(mut self, schemes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.url_schemes.extend(schemes.into_iter().map(Into::into));
+        self
+    }
+
+    /// Parse and sanitize a string of untrusted HTML.
+    ///
+    /// The input is parsed with [`parse_lenient`], so malformed HTML (like
+    /// elements which are left open or closed in the wrong order, which is
+    /// common in user-written HTML) is accepted.
+    #[must_use]
+    pub fn sanitize_html(&self, html: &str) -> Node {
+        self.sanitize(parse_lenient(html))
+    }
+
+    /// Sanitize an untrusted [`Node`] tree.
+    #[must_use]
+    pub fn sanitize(&self, node: Node) -> Node {
+        let mut sanitized = Vec::new();
+        self.sanitize_into(node, &mut sanitized);
+
+        if sanitized.len() == 1 {
+            sanitized.remove(0)
+        } else {
+            Node::Fragment(Fragment {
+                children: sanitized,
+            })
+        }
+    }
+
+    fn sanitize_into(&self, node: Node, sanitized: &mut Vec<Node>) {
+        match node {
+            Node::Comment(comment) => {
+                if self.allow_comments {
+                    sanitized.push(Node::Comment(comment));
+                }
+            }
+            Node::Doctype(_) | Node::ProcessingInstruction(_) => {}
+            Node::Cdata(cdata) => sanitized.push(Node::Text(Text { text: cdata.text })),
+            Node::Fragment(fragment) => {
+                for child in fragment.children {
+                    self.sanitize_into(child, sanitized);
+                }
+            }
+            Node::Element(element) => self.sanitize_element(element, sanitized),
+            Node::Lazy(lazy) => self.sanitize_into(lazy.evaluate(), sanitized),
+            Node::Shared(shared) => self.sanitize_into(Arc::unwrap_or_clone(shared), sanitized),
+            Node::Text(text) => sanitized.push(Node::Text(text)),
+            Node::RawText(text) => sanitized.push(Node::Text(Text { text: text.text })),
+            Node::UnsafeText(text) => self.sanitize_into(parse_lenient(&text.text), sanitized),
+        }
+    }
+
+    fn sanitize_element(&self, element: Element, sanitized: &mut Vec<Node>) {
+        let name = element.name.to_ascii_lowercase();
+
+        if self.removed_elements.contains(&name) {
+            return;
+        }
+
+        let children = element.children.map(|children| {
+            let mut sanitized_children = Vec::new();
+
+            for child in children {
+                self.sanitize_into(child, &mut sanitized_children);
+            }
+
+            sanitized_children
+        });
+
+        if !self.elements.contains(&name) {
+            sanitized.extend(children.into_iter().flatten());
+            return;
+        }
+
+        let attributes = element
+            .attributes
+            .into_iter()
+            .filter(|(key, value)| self.is_allowed_attribute(&name, key, value.as_deref()))
+            .collect();
+
+        sanitized.push(Node::Element(Element {
+            name: element.name,
+            attributes,
+            children,
+        }));
+    }
+
+    fn is_allowed_attribute(&self, element: &str, key: &str, value: Option<&str>) -> bool {
+        let key = key.to_ascii_lowercase();
+
+        if key.starts_with("on") {
+            return false;
+        }
+
+        let is_allowed = [element, "*"].into_iter().any(|element| {
+            self.attributes
+                .get(element)
+                .is_some_and(|attributes| attributes.contains(&key))
+        });
+
+        is_allowed
+            && (!self.url_attributes.contains(&key)
+                || value.is_none_or(|value| self.is_allowed_url(value)))
+    }
+
+    fn is_allowed_url(&self, url: &str) -> bool {
+        let (url, scheme_len) = escape::normalize_url(url);
+
+        scheme_len.is_none_or(|len| self.url_schemes.contains(&url[..len].to_ascii_lowercase()))
+    }
+}
@@ -0,0 +1,711 @@
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    ptr,
+    str::FromStr,
+    sync::Arc,
+};
+
+use crate::{Element, Node};
+
+/// A parsed CSS selector, or list of selectors.
+///
+/// The supported syntax is:
+///
+/// - type (`div`), universal (`*`), class (`.class`) and id (`#id`) selectors.
+/// - attribute selectors: `[attr]`, `[attr=value]`, `[attr~=value]`,
+///   `[attr|=value]`, `[attr^=value]`, `[attr$=value]` and `[attr*=value]`,
+///   optionally followed by an `i` to compare the value case-insensitively.
+/// - the `:first-child`, `:last-child` and `:not(...)` pseudo-classes.
+/// - the descendant (`a b`) and child (`a > b`) combinators.
+/// - selector lists (`a, b`).
+///
+/// Element and attribute names are matched case-insensitively, as in HTML.
+/// Fragments are transparent: the children of a fragment are treated as
+/// children of the fragment's parent.
+///
+/// ```rust
+/// use html_node_core::{select::Selector, Element, Node, Text};
+///
+/// let node = Node::from(Element {
+///     name: "nav".into(),
+///     attributes: Vec::new(),
+///     children: Some(vec![
+///         Element {
+///             name: "a".into(),
+///             attributes: vec![("href".into(), Some("/".into()))],
+///             children: Some(vec![Text::from("home").into()]),
+///         }
+///         .into(),
+///         Element {
+///             name: "a".into(),
+///             attributes: vec![("href".into(), Some("https://example.com".into()))],
+///             children: Some(vec![Text::from("example").into()]),
+///         }
+///         .into(),
+///     ]),
+/// });
+///
+/// let selector = "nav > a[href^=http]".parse::<Selector>().unwrap();
+/// let external = selector.select(&node).collect::<Vec<_>>();
+///
+/// assert_eq!(external.len(), 1);
+/// assert_eq!(external[0].to_string(), r#"<a href="https://example.com">example</a>"#);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    alternatives: Vec<Complex>,
+}
+
+impl Selector {
+    /// Parse a CSS selector.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SelectorError`] if the selector is invalid or uses
+    /// unsupported syntax.
+    pub fn parse(selector: &str) -> Result<Self, SelectorError> {
+        let mut parser = SelectorParser {
+            input: selector,
+            pos: 0,
+        };
+
+        let selector = parser.selector_list()?;
+
+        if parser.peek().is_some() {
+            return Err(parser.unexpected());
+        }
+
+        Ok(selector)
+    }
+
+    /// Whether the element matches the selector.
+    ///
+    /// `parents` are the ancestors of the element, starting from the root of
+    /// the tree, like [`Visited::parents`](crate::visit::Visited::parents).
+    #[must_use]
+    pub fn matches(&self, element: &Element, parents: &[&Node]) -> bool {
+        self.alternatives
+            .iter()
+            .any(|complex| complex.matches(element, parents))
+    }
+
+    /// Iterate over the elements in the tree (including `node` itself) which
+    /// match the selector, in document order.
+    pub fn select<'s, 'a>(
+        &'s self,
+        node: &'a Node,
+    ) -> impl Iterator<Item = &'a Element> + use<'s, 'a> {
+        node.dfs().filter_map(|visited| {
+            visited
+                .node
+                .as_element()
+                .filter(|element| self.matches(element, &visited.parents))
+        })
+    }
+
+    /// The first element in the tree (including `node` itself) which matches
+    /// the selector, in document order.
+    #[must_use]
+    pub fn select_first<'a>(&self, node: &'a Node) -> Option<&'a Element> {
+        node.dfs().find_map(|visited| {
+            visited
+                .node
+                .as_element()
+                .filter(|element| self.matches(element, &visited.parents))
+        })
+    }
+
+    /// The first element in the tree (including `node` itself) which matches
+    /// the selector, in document order, borrowed mutably.
+    #[must_use]
+    pub fn select_first_mut<'a>(&self, node: &'a mut Node) -> Option<&'a mut Element> {
+        let path = self.paths(node).into_iter().next()?;
+
+        match node_at_mut(node, &path) {
+            Some(Node::Element(element)) => Some(element),
+            _ => None,
+        }
+    }
+
+    /// Call `f` on every element in the tree (including `node` itself) which
+    /// matches the selector.
+    ///
+    /// The matching elements are all found before `f` is called, and `f` is
+    /// called on them in reverse document order, so that changes made by `f`
+    /// to an element (or to its children) don't affect which elements are
+    /// visited.
+    pub fn select_mut(&self, node: &mut Node, mut f: impl FnMut(&mut Element)) {
+        for path in self.paths(node).into_iter().rev() {
+            if let Some(Node::Element(element)) = node_at_mut(node, &path) {
+                f(element);
+            }
+        }
+    }
+
+    /// The paths (as child indices from `node`) of the matching elements, in
+    /// document order.
+    fn paths(&self, node: &Node) -> Vec<Vec<usize>> {
+        fn walk<'a>(
+            selector: &Selector,
+            node: &'a Node,
+            parents: &mut Vec<&'a Node>,
+            path: &mut Vec<usize>,
+            paths: &mut Vec<Vec<usize>>,
+        ) {
+            if let Node::Element(element) = node {
+                if selector.matches(element, parents) {
+                    paths.push(path.clone());
+                }
+            }
+
+            parents.push(node);
+
+            for (i, child) in node.children_iter().enumerate() {
+                path.push(i);
+                walk(selector, child, parents, path, paths);
+                path.pop();
+            }
+
+            parents.pop();
+        }
+
+        let mut paths = Vec::new();
+        walk(self, node, &mut Vec::new(), &mut Vec::new(), &mut paths);
+        paths
+    }
+}
+
+impl FromStr for Selector {
+    type Err = SelectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// An error encountered while parsing a [`Selector`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorError {
+    /// What went wrong.
+    pub kind: SelectorErrorKind,
+
+    /// The byte offset into the selector where the error occurred.
+    pub offset: usize,
+}
+
+impl Display for SelectorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.kind, self.offset)
+    }
+}
+
+impl Error for SelectorError {}
+
+/// The kind of a [`SelectorError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SelectorErrorKind {
+    /// The selector ended where more was expected.
+    UnexpectedEof,
+
+    /// A character which isn't allowed at this point in the selector.
+    UnexpectedChar(char),
+
+    /// A pseudo-class which isn't supported.
+    UnsupportedPseudoClass(String),
+}
+
+impl Display for SelectorErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof => write!(f, "unexpected end of selector"),
+            Self::UnexpectedChar(c) => write!(f, "unexpected character `{c}`"),
+            Self::UnsupportedPseudoClass(name) => {
+                write!(f, "unsupported pseudo-class `:{name}`")
+            }
+        }
+    }
+}
+
+/// A sequence of compound selectors joined by combinators, like `nav > a`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Complex {
+    compounds: Vec<Compound>,
+
+    /// The combinator between each compound and the next.
+    combinators: Vec<Combinator>,
+}
+
+impl Complex {
+    fn matches(&self, element: &Element, parents: &[&Node]) -> bool {
+        self.matches_from(self.compounds.len() - 1, element, parents)
+    }
+
+    /// Whether the element matches the compound at `i`, and its ancestors
+    /// match the compounds before it.
+    fn matches_from(&self, i: usize, element: &Element, parents: &[&Node]) -> bool {
+        if !self.compounds[i].matches(element, parents) {
+            return false;
+        }
+
+        let Some(i) = i.checked_sub(1) else {
+            return true;
+        };
+
+        let mut ancestors = parents
+            .iter()
+            .enumerate()
+            .rev()
+            .filter_map(|(k, node)| node.as_element().map(|element| (element, &parents[..k])));
+
+        match self.combinators[i] {
+            Combinator::Child => ancestors
+                .next()
+                .is_some_and(|(parent, parents)| self.matches_from(i, parent, parents)),
+            Combinator::Descendant => {
+                ancestors.any(|(ancestor, parents)| self.matches_from(i, ancestor, parents))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+/// A type selector and conditions on the same element, like `a.link[href]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Compound {
+    /// The element name, or `None` for the universal selector.
+    name: Option<String>,
+    conditions: Vec<Condition>,
+}
+
+impl Compound {
+    fn matches(&self, element: &Element, parents: &[&Node]) -> bool {
+        self.name
+            .as_ref()
+            .is_none_or(|name| element.name.eq_ignore_ascii_case(name))
+            && self
+                .conditions
+                .iter()
+                .all(|condition| condition.matches(element, parents))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    Id(String),
+    Class(String),
+    Attribute {
+        name: String,
+        matcher: Option<AttributeMatcher>,
+    },
+    FirstChild,
+    LastChild,
+    Not(Selector),
+}
+
+impl Condition {
+    fn matches(&self, element: &Element, parents: &[&Node]) -> bool {
+        match self {
+            Self::Id(id) => attribute(element, "id").is_some_and(|value| value == id),
+            Self::Class(class) => attribute(element, "class")
+                .is_some_and(|value| value.split_ascii_whitespace().any(|c| c == class)),
+            Self::Attribute { name, matcher } => attribute(element, name).is_some_and(|value| {
+                matcher
+                    .as_ref()
+                    .is_none_or(|matcher| matcher.matches(value))
+            }),
+            Self::FirstChild => siblings(parents).is_none_or(|siblings| {
+                siblings
+                    .first()
+                    .is_some_and(|&first| ptr::eq(first, element))
+            }),
+            Self::LastChild => siblings(parents)
+                .is_none_or(|siblings| siblings.last().is_some_and(|&last| ptr::eq(last, element))),
+            Self::Not(selector) => !selector.matches(element, parents),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AttributeMatcher {
+    operator: AttributeOperator,
+    value: String,
+    ignore_case: bool,
+}
+
+impl AttributeMatcher {
+    fn matches(&self, value: &str) -> bool {
+        let (value, expected) = if self.ignore_case {
+            (
+                Cow::Owned(value.to_lowercase()),
+                Cow::Owned(self.value.to_lowercase()),
+            )
+        } else {
+            (Cow::Borrowed(value), Cow::Borrowed(self.value.as_str()))
+        };
+
+        match self.operator {
+            AttributeOperator::Equals => value == expected,
+            AttributeOperator::Includes => {
+                value.split_ascii_whitespace().any(|word| word == expected)
+            }
+            AttributeOperator::DashMatch => {
+                value == expected
+                    || value
+                        .strip_prefix(&*expected)
+                        .is_some_and(|rest| rest.starts_with('-'))
+            }
+            AttributeOperator::Prefix => !expected.is_empty() && value.starts_with(&*expected),
+            AttributeOperator::Suffix => !expected.is_empty() && value.ends_with(&*expected),
+            AttributeOperator::Substring => !expected.is_empty() && value.contains(&*expected),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AttributeOperator {
+    /// `=`
+    Equals,
+    /// `~=`
+    Includes,
+    /// `|=`
+    DashMatch,
+    /// `^=`
+    Prefix,
+    /// `$=`
+    Suffix,
+    /// `*=`
+    Substring,
+}
+
+/// The value of the attribute with the given name, where attributes with no
+/// value have an empty one.
+fn attribute<'a>(element: &'a Element, name: &str) -> Option<&'a str> {
+    element
+        .attributes
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_deref().unwrap_or_default())
+}
+
+/// The element children of the parent of an element (looking through
+/// fragments), or `None` if it is the root.
+fn siblings<'a>(parents: &[&'a Node]) -> Option<Vec<&'a Element>> {
+    fn element_children<'a>(node: &'a Node, siblings: &mut Vec<&'a Element>) {
+        for child in node.children_iter() {
+            match child {
+                Node::Fragment(_) | Node::Shared(_) => element_children(child, siblings),
+                Node::Element(element) => siblings.push(element),
+                _ => {}
+            }
+        }
+    }
+
+    let parent = parents
+        .iter()
+        .rev()
+        .find(|node| matches!(node, Node::Element(_)))
+        .or_else(|| parents.first())?;
+
+    let mut siblings = Vec::new();
+    element_children(parent, &mut siblings);
+    Some(siblings)
+}
+
+/// The node at the given path of child indices.
+fn node_at_mut<'a>(mut node: &'a mut Node, path: &[usize]) -> Option<&'a mut Node> {
+    for &i in path {
+        let children = match node {
+            Node::Fragment(fragment) => &mut fragment.children,
+            Node::Element(element) => element.children.as_mut()?,
+            Node::Shared(shared) => std::slice::from_mut(Arc::make_mut(shared)),
+            _ => return None,
+        };
+
+        node = children.get_mut(i)?;
+    }
+
+    Some(node)
+}
+
+struct SelectorParser<'s> {
+    input: &'s str,
+    pos: usize,
+}
+
+impl SelectorParser<'_> {
+    /// Parse a comma-separated list of selectors, up until the end of the
+    /// input or a `)`.
+    fn selector_list(&mut self) -> Result<Selector, SelectorError> {
+        let mut alternatives = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            alternatives.push(self.complex()?);
+            self.skip_whitespace();
+
+            if !self.eat(',') {
+                return Ok(Selector { alternatives });
+            }
+        }
+    }
+
+    fn complex(&mut self) -> Result<Complex, SelectorError> {
+        let mut compounds = vec![self.compound()?];
+        let mut combinators = Vec::new();
+
+        loop {
+            let had_whitespace = self.skip_whitespace();
+
+            let combinator = match self.peek() {
+                Some('>') => {
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    Combinator::Child
+                }
+                None | Some(',' | ')') => {
+                    return Ok(Complex {
+                        compounds,
+                        combinators,
+                    })
+                }
+                Some(_) if had_whitespace => Combinator::Descendant,
+                Some(c) => return Err(self.error(SelectorErrorKind::UnexpectedChar(c))),
+            };
+
+            combinators.push(combinator);
+            compounds.push(self.compound()?);
+        }
+    }
+
+    fn compound(&mut self) -> Result<Compound, SelectorError> {
+        let universal = self.eat('*');
+
+        let name = if !universal && self.peek().is_some_and(is_identifier_char) {
+            Some(self.identifier()?.to_ascii_lowercase())
+        } else {
+            None
+        };
+
+        let mut conditions = Vec::new();
+
+        loop {
+            let condition = match self.peek() {
+                Some('#') => {
+                    self.pos += 1;
+                    Condition::Id(self.identifier()?)
+                }
+                Some('.') => {
+                    self.pos += 1;
+                    Condition::Class(self.identifier()?)
+                }
+                Some('[') => {
+                    self.pos += 1;
+                    self.attribute()?
+                }
+                Some(':') => {
+                    self.pos += 1;
+                    self.pseudo_class()?
+                }
+                _ => break,
+            };
+
+            conditions.push(condition);
+        }
+
+        if !universal && name.is_none() && conditions.is_empty() {
+            return Err(self.unexpected());
+        }
+
+        Ok(Compound { name, conditions })
+    }
+
+    fn attribute(&mut self) -> Result<Condition, SelectorError> {
+        self.skip_whitespace();
+        let name = self.identifier()?.to_ascii_lowercase();
+        self.skip_whitespace();
+
+        if self.eat(']') {
+            return Ok(Condition::Attribute {
+                name,
+                matcher: None,
+            });
+        }
+
+        let operator = if self.eat('=') {
+            AttributeOperator::Equals
+        } else {
+            let operator = match self.peek() {
+                Some('~') => AttributeOperator::Includes,
+                Some('|') => AttributeOperator::DashMatch,
+                Some('^') => AttributeOperator::Prefix,
+                Some('$') => AttributeOperator::Suffix,
+                Some('*') => AttributeOperator::Substring,
+                _ => return Err(self.unexpected()),
+            };
+
+            self.pos += 1;
+            self.expect('=')?;
+            operator
+        };
+
+        self.skip_whitespace();
+        let value = match self.peek() {
+            Some('"' | '\'') => self.string()?,
+            _ => self.identifier()?,
+        };
+        self.skip_whitespace();
+
+        let ignore_case = match self.peek() {
+            Some('i' | 'I') => true,
+            Some('s' | 'S') => false,
+            _ => {
+                self.expect(']')?;
+                return Ok(Condition::Attribute {
+                    name,
+                    matcher: Some(AttributeMatcher {
+                        operator,
+                        value,
+                        ignore_case: false,
+                    }),
+                });
+            }
+        };
+
+        self.pos += 1;
+        self.skip_whitespace();
+        self.expect(']')?;
+
+        Ok(Condition::Attribute {
+            name,
+            matcher: Some(AttributeMatcher {
+                operator,
+                value,
+                ignore_case,
+            }),
+        })
+    }
+
+    fn pseudo_class(&mut self) -> Result<Condition, SelectorError> {
+        let start = self.pos;
+        let name = self.identifier()?.to_ascii_lowercase();
+
+        match name.as_str() {
+            "first-child" => Ok(Condition::FirstChild),
+            "last-child" => Ok(Condition::LastChild),
+            "not" => {
+                self.expect('(')?;
+                let selector = self.selector_list()?;
+                self.expect(')')?;
+
+                Ok(Condition::Not(selector))
+            }
+            _ => Err(SelectorError {
+                kind: SelectorErrorKind::UnsupportedPseudoClass(name),
+                offset: start,
+            }),
+        }
+    }
+
+    /// Parse an identifier, resolving backslash escapes.
+    fn identifier(&mut self) -> Result<String, SelectorError> {
+        let mut identifier = String::new();
+
+        while let Some(c) = self.peek().filter(|&c| is_identifier_char(c)) {
+            self.pos += c.len_utf8();
+
+            if c == '\\' {
+                let escaped = self.peek().ok_or_else(|| self.unexpected())?;
+                self.pos += escaped.len_utf8();
+                identifier.push(escaped);
+            } else {
+                identifier.push(c);
+            }
+        }
+
+        if identifier.is_empty() {
+            Err(self.unexpected())
+        } else {
+            Ok(identifier)
+        }
+    }
+
+    /// Parse a quoted string, resolving backslash escapes.
+    fn string(&mut self) -> Result<String, SelectorError> {
+        let quote = self.peek().ok_or_else(|| self.unexpected())?;
+        self.pos += 1;
+
+        let mut string = String::new();
+
+        loop {
+            let c = self.peek().ok_or_else(|| self.unexpected())?;
+            self.pos += c.len_utf8();
+
+            match c {
+                '\\' => {
+                    let escaped = self.peek().ok_or_else(|| self.unexpected())?;
+                    self.pos += escaped.len_utf8();
+                    string.push(escaped);
+                }
+                c if c == quote => return Ok(string),
+                c => string.push(c),
+            }
+        }
+    }
+
+    /// Skip whitespace, returning whether there was any.
+    fn skip_whitespace(&mut self) -> bool {
+        let rest = &self.input[self.pos..];
+        let len = rest.len() - rest.trim_start().len();
+
+        self.pos += len;
+        len > 0
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SelectorError> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    /// An error for whatever is at the current position.
+    fn unexpected(&self) -> SelectorError {
+        self.error(self.peek().map_or(
+            SelectorErrorKind::UnexpectedEof,
+            SelectorErrorKind::UnexpectedChar,
+        ))
+    }
+
+    const fn error(&self, kind: SelectorErrorKind) -> SelectorError {
+        SelectorError {
+            kind,
+            offset: self.pos,
+        }
+    }
+}
+
+const fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '\\') || !c.is_ascii()
+}
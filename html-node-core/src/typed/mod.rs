@@ -12,6 +12,12 @@ pub trait TypedElement {
     /// The attributes of the element.
     type Attributes;
 
+    /// The names of the attributes which must be given a value, each with the
+    /// error for when it isn't.
+    ///
+    /// `typed::html!` checks these at compile time.
+    const REQUIRED_ATTRIBUTES: &'static [(&'static str, &'static str)] = &[];
+
     /// Create an element from its attributes.
     fn from_attributes(
         attributes: Self::Attributes,
@@ -22,6 +28,48 @@ pub trait TypedElement {
     fn into_node(self, children: Option<Vec<Node>>) -> Node;
 }
 
+/// Fail with the error of the first of the `required` attributes which isn't
+/// `given` (with a value).
+///
+/// Used by `typed::html!` in a constant, to fail at compile time.
+#[doc(hidden)]
+pub const fn check_required_attributes(required: &[(&str, &str)], given: &[&str]) {
+    let mut i = 0;
+
+    while i < required.len() {
+        let (name, error) = required[i];
+
+        let mut j = 0;
+        while j < given.len() && !str_eq(name, given[j]) {
+            j += 1;
+        }
+
+        assert!(j < given.len(), "{}", error);
+
+        i += 1;
+    }
+}
+
+/// [`str`] equality, which isn't `const` yet.
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+
+        i += 1;
+    }
+
+    true
+}
+
 /// A typed set of HTML attributes.
 pub trait TypedAttributes {
     /// Convert the typed attributes into a set of attributes.
@@ -132,9 +180,10 @@ macro_rules! typed_element {
                 Self { attributes, other_attributes }
             }
 
-            fn into_node(mut self, children: ::std::option::Option<::std::vec::Vec<$crate::Node>>) -> $crate::Node {
+            fn into_node(self, children: ::std::option::Option<::std::vec::Vec<$crate::Node>>) -> $crate::Node {
                 let mut attributes = $crate::typed::TypedAttributes::into_attributes(self.attributes);
-                attributes.append(&mut self.other_attributes);
+                // other attributes (like spread ones) override the typed ones.
+                $crate::spread_attributes(&mut attributes, self.other_attributes);
 
                 $crate::Node::Element(
                     $crate::Element {
@@ -0,0 +1,502 @@
+//! Each trait has a method per kind of node.
+//!
+//! By default, the methods do nothing for leaf nodes, and walk the children of
+//! elements and fragments. Overriding a method and calling the free function
+//! with the same name (like [`visit_element`]) keeps walking the children.
+//!
+//! ```rust
+//! use html_node_core::{
+//!     visit::{self, Visit},
+//!     Element, Node,
+//! };
+//!
+//! struct Ids(Vec<String>);
+//!
+//! impl Visit for Ids {
+//!     fn visit_element(&mut self, element: &Element) {
+//!         for (key, value) in &element.attributes {
+//!             if let ("id", Some(value)) = (key.as_str(), value) {
+//!                 self.0.push(value.clone());
+//!             }
+//!         }
+//!
+//!         visit::visit_element(self, element);
+//!     }
+//! }
+//!
+//! let node = Node::from(Element {
+//!     name: "div".into(),
+//!     attributes: vec![("id".into(), Some("outer".into()))],
+//!     children: Some(vec![Node::from(Element {
+//!         name: "span".into(),
+//!         attributes: vec![("id".into(), Some("inner".into()))],
+//!         children: Some(Vec::new()),
+//!     })]),
+//! });
+//!
+//! let mut ids = Ids(Vec::new());
+//! ids.visit_node(&node);
+//!
+//! assert_eq!(ids.0, ["outer", "inner"]);
+//! ```
+
+use std::{collections::VecDeque, sync::Arc};
+
+use crate::{
+    Cdata, Comment, Doctype, Element, Fragment, Lazy, Node, ProcessingInstruction, RawText, Text,
+    UnsafeText,
+};
+
+/// A visitor over a borrowed [`Node`] tree.
+///
+/// See the [module-level documentation](self) for more information.
+pub trait Visit {
+    /// Visit a node, dispatching to the method for its kind.
+    fn visit_node(&mut self, node: &Node) {
+        visit_node(self, node);
+    }
+
+    /// Visit a comment.
+    fn visit_comment(&mut self, _comment: &Comment) {}
+
+    /// Visit a doctype.
+    fn visit_doctype(&mut self, _doctype: &Doctype) {}
+
+    /// Visit a CDATA section.
+    fn visit_cdata(&mut self, _cdata: &Cdata) {}
+
+    /// Visit a processing instruction.
+    fn visit_processing_instruction(&mut self, _processing_instruction: &ProcessingInstruction) {}
+
+    /// Visit a fragment, and then its children.
+    fn visit_fragment(&mut self, fragment: &Fragment) {
+        visit_fragment(self, fragment);
+    }
+
+    /// Visit an element, and then its children.
+    fn visit_element(&mut self, element: &Element) {
+        visit_element(self, element);
+    }
+
+    /// Visit a text node.
+    fn visit_text(&mut self, _text: &Text) {}
+
+    /// Visit a raw text node.
+    fn visit_raw_text(&mut self, _raw_text: &RawText) {}
+
+    /// Visit a shared node, and then the node it contains.
+    fn visit_shared(&mut self, shared: &Arc<Node>) {
+        self.visit_node(shared);
+    }
+
+    /// Visit a lazy node, which is opaque.
+    fn visit_lazy(&mut self, _lazy: &Lazy) {}
+
+    /// Visit an unsafe text node.
+    fn visit_unsafe_text(&mut self, _unsafe_text: &UnsafeText) {}
+}
+
+/// Dispatch a node to the [`Visit`] method for its kind.
+pub fn visit_node<V: Visit + ?Sized>(visitor: &mut V, node: &Node) {
+    match node {
+        Node::Comment(comment) => visitor.visit_comment(comment),
+        Node::Doctype(doctype) => visitor.visit_doctype(doctype),
+        Node::Cdata(cdata) => visitor.visit_cdata(cdata),
+        Node::ProcessingInstruction(processing_instruction) => {
+            visitor.visit_processing_instruction(processing_instruction);
+        }
+        Node::Fragment(fragment) => visitor.visit_fragment(fragment),
+        Node::Element(element) => visitor.visit_element(element),
+        Node::Text(text) => visitor.visit_text(text),
+        Node::RawText(raw_text) => visitor.visit_raw_text(raw_text),
+        Node::Shared(shared) => visitor.visit_shared(shared),
+        Node::Lazy(lazy) => visitor.visit_lazy(lazy),
+        Node::UnsafeText(unsafe_text) => visitor.visit_unsafe_text(unsafe_text),
+    }
+}
+
+/// Visit the children of a fragment.
+pub fn visit_fragment<V: Visit + ?Sized>(visitor: &mut V, fragment: &Fragment) {
+    for child in &fragment.children {
+        visitor.visit_node(child);
+    }
+}
+
+/// Visit the children of an element.
+pub fn visit_element<V: Visit + ?Sized>(visitor: &mut V, element: &Element) {
+    for child in element.children.iter().flatten() {
+        visitor.visit_node(child);
+    }
+}
+
+/// A visitor over a mutably borrowed [`Node`] tree.
+///
+/// See the [module-level documentation](self) for more information.
+///
+/// ```rust
+/// use html_node_core::{
+///     visit::{self, VisitMut},
+///     Element, Node,
+/// };
+///
+/// struct Nonce(&'static str);
+///
+/// impl VisitMut for Nonce {
+///     fn visit_element_mut(&mut self, element: &mut Element) {
+///         if element.name == "script" {
+///             element
+///                 .attributes
+///                 .push(("nonce".into(), Some(self.0.into())));
+///         }
+///
+///         visit::visit_element_mut(self, element);
+///     }
+/// }
+///
+/// let mut node = Node::from(Element {
+///     name: "script".into(),
+///     attributes: Vec::new(),
+///     children: Some(Vec::new()),
+/// });
+///
+/// Nonce("abc").visit_node_mut(&mut node);
+///
+/// assert_eq!(node.to_string(), r#"<script nonce="abc"></script>"#);
+/// ```
+pub trait VisitMut {
+    /// Visit a node, dispatching to the method for its kind.
+    fn visit_node_mut(&mut self, node: &mut Node) {
+        visit_node_mut(self, node);
+    }
+
+    /// Visit a comment.
+    fn visit_comment_mut(&mut self, _comment: &mut Comment) {}
+
+    /// Visit a doctype.
+    fn visit_doctype_mut(&mut self, _doctype: &mut Doctype) {}
+
+    /// Visit a CDATA section.
+    fn visit_cdata_mut(&mut self, _cdata: &mut Cdata) {}
+
+    /// Visit a processing instruction.
+    fn visit_processing_instruction_mut(
+        &mut self,
+        _processing_instruction: &mut ProcessingInstruction,
+    ) {
+    }
+
+    /// Visit a fragment, and then its children.
+    fn visit_fragment_mut(&mut self, fragment: &mut Fragment) {
+        visit_fragment_mut(self, fragment);
+    }
+
+    /// Visit an element, and then its children.
+    fn visit_element_mut(&mut self, element: &mut Element) {
+        visit_element_mut(self, element);
+    }
+
+    /// Visit a text node.
+    fn visit_text_mut(&mut self, _text: &mut Text) {}
+
+    /// Visit a raw text node.
+    fn visit_raw_text_mut(&mut self, _raw_text: &mut RawText) {}
+
+    /// Visit a shared node, and then the node it contains.
+    ///
+    /// The node is cloned first if it is still shared (see
+    /// [`Arc::make_mut`]), so override this to leave shared nodes untouched.
+    fn visit_shared_mut(&mut self, shared: &mut Arc<Node>) {
+        self.visit_node_mut(Arc::make_mut(shared));
+    }
+
+    /// Visit a lazy node, which is opaque.
+    fn visit_lazy_mut(&mut self, _lazy: &mut Lazy) {}
+
+    /// Visit an unsafe text node.
+    fn visit_unsafe_text_mut(&mut self, _unsafe_text: &mut UnsafeText) {}
+}
+
+/// Dispatch a node to the [`VisitMut`] method for its kind.
+pub fn visit_node_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Node) {
+    match node {
+        Node::Comment(comment) => visitor.visit_comment_mut(comment),
+        Node::Doctype(doctype) => visitor.visit_doctype_mut(doctype),
+        Node::Cdata(cdata) => visitor.visit_cdata_mut(cdata),
+        Node::ProcessingInstruction(processing_instruction) => {
+            visitor.visit_processing_instruction_mut(processing_instruction);
+        }
+        Node::Fragment(fragment) => visitor.visit_fragment_mut(fragment),
+        Node::Element(element) => visitor.visit_element_mut(element),
+        Node::Text(text) => visitor.visit_text_mut(text),
+        Node::RawText(raw_text) => visitor.visit_raw_text_mut(raw_text),
+        Node::Shared(shared) => visitor.visit_shared_mut(shared),
+        Node::Lazy(lazy) => visitor.visit_lazy_mut(lazy),
+        Node::UnsafeText(unsafe_text) => visitor.visit_unsafe_text_mut(unsafe_text),
+    }
+}
+
+/// Visit the children of a fragment.
+pub fn visit_fragment_mut<V: VisitMut + ?Sized>(visitor: &mut V, fragment: &mut Fragment) {
+    for child in &mut fragment.children {
+        visitor.visit_node_mut(child);
+    }
+}
+
+/// Visit the children of an element.
+pub fn visit_element_mut<V: VisitMut + ?Sized>(visitor: &mut V, element: &mut Element) {
+    for child in element.children.iter_mut().flatten() {
+        visitor.visit_node_mut(child);
+    }
+}
+
+/// A folder over an owned [`Node`] tree, rebuilding it bottom-up.
+///
+/// Each method returns the node which replaces the one it was given, which
+/// doesn't have to be of the same kind. Returning [`Node::EMPTY`] removes a
+/// node, as empty fragments are dropped from the children of elements and
+/// fragments.
+///
+/// See the [module-level documentation](self) for more information.
+///
+/// ```rust
+/// use html_node_core::{visit::Fold, Comment, Element, Node, Text};
+///
+/// struct StripComments;
+///
+/// impl Fold for StripComments {
+///     fn fold_comment(&mut self, _comment: Comment) -> Node {
+///         Node::EMPTY
+///     }
+/// }
+///
+/// let node = Node::from(Element {
+///     name: "p".into(),
+///     attributes: Vec::new(),
+///     children: Some(vec![
+///         Comment::from("secret").into(),
+///         Text::from("hello").into(),
+///     ]),
+/// });
+///
+/// assert_eq!(StripComments.fold_node(node).to_string(), "<p>hello</p>");
+/// ```
+pub trait Fold {
+    /// Fold a node, dispatching to the method for its kind.
+    fn fold_node(&mut self, node: Node) -> Node {
+        fold_node(self, node)
+    }
+
+    /// Fold a comment.
+    fn fold_comment(&mut self, comment: Comment) -> Node {
+        Node::Comment(comment)
+    }
+
+    /// Fold a doctype.
+    fn fold_doctype(&mut self, doctype: Doctype) -> Node {
+        Node::Doctype(doctype)
+    }
+
+    /// Fold a CDATA section.
+    fn fold_cdata(&mut self, cdata: Cdata) -> Node {
+        Node::Cdata(cdata)
+    }
+
+    /// Fold a processing instruction.
+    fn fold_processing_instruction(
+        &mut self,
+        processing_instruction: ProcessingInstruction,
+    ) -> Node {
+        Node::ProcessingInstruction(processing_instruction)
+    }
+
+    /// Fold the children of a fragment, and then the fragment itself.
+    fn fold_fragment(&mut self, fragment: Fragment) -> Node {
+        Node::Fragment(fold_fragment(self, fragment))
+    }
+
+    /// Fold the children of an element, and then the element itself.
+    fn fold_element(&mut self, element: Element) -> Node {
+        Node::Element(fold_element(self, element))
+    }
+
+    /// Fold a text node.
+    fn fold_text(&mut self, text: Text) -> Node {
+        Node::Text(text)
+    }
+
+    /// Fold a raw text node.
+    fn fold_raw_text(&mut self, raw_text: RawText) -> Node {
+        Node::RawText(raw_text)
+    }
+
+    /// Fold the node a shared node contains.
+    ///
+    /// The node is cloned first if it is still shared (see
+    /// [`Arc::unwrap_or_clone`]), so override this to leave shared nodes
+    /// untouched.
+    fn fold_shared(&mut self, shared: Arc<Node>) -> Node {
+        Node::Shared(Arc::new(self.fold_node(Arc::unwrap_or_clone(shared))))
+    }
+
+    /// Fold a lazy node, which is opaque.
+    fn fold_lazy(&mut self, lazy: Lazy) -> Node {
+        Node::Lazy(lazy)
+    }
+
+    /// Fold an unsafe text node.
+    fn fold_unsafe_text(&mut self, unsafe_text: UnsafeText) -> Node {
+        Node::UnsafeText(unsafe_text)
+    }
+}
+
+/// Dispatch a node to the [`Fold`] method for its kind.
+pub fn fold_node<F: Fold + ?Sized>(folder: &mut F, node: Node) -> Node {
+    match node {
+        Node::Comment(comment) => folder.fold_comment(comment),
+        Node::Doctype(doctype) => folder.fold_doctype(doctype),
+        Node::Cdata(cdata) => folder.fold_cdata(cdata),
+        Node::ProcessingInstruction(processing_instruction) => {
+            folder.fold_processing_instruction(processing_instruction)
+        }
+        Node::Fragment(fragment) => folder.fold_fragment(fragment),
+        Node::Element(element) => folder.fold_element(element),
+        Node::Text(text) => folder.fold_text(text),
+        Node::RawText(raw_text) => folder.fold_raw_text(raw_text),
+        Node::Shared(shared) => folder.fold_shared(shared),
+        Node::Lazy(lazy) => folder.fold_lazy(lazy),
+        Node::UnsafeText(unsafe_text) => folder.fold_unsafe_text(unsafe_text),
+    }
+}
+
+/// Fold the children of a fragment.
+pub fn fold_fragment<F: Fold + ?Sized>(folder: &mut F, fragment: Fragment) -> Fragment {
+    Fragment {
+        children: fold_children(folder, fragment.children),
+    }
+}
+
+/// Fold the children of an element.
+pub fn fold_element<F: Fold + ?Sized>(folder: &mut F, element: Element) -> Element {
+    Element {
+        children: element
+            .children
+            .map(|children| fold_children(folder, children)),
+        ..element
+    }
+}
+
+fn fold_children<F: Fold + ?Sized>(folder: &mut F, children: Vec<Node>) -> Vec<Node> {
+    children
+        .into_iter()
+        .map(|child| folder.fold_node(child))
+        .filter(|child| !matches!(child, Node::Fragment(fragment) if fragment.children.is_empty()))
+        .collect()
+}
+
+/// A node reached by a [`Dfs`] or [`Bfs`] traversal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Visited<'a> {
+    /// The node.
+    pub node: &'a Node,
+
+    /// The ancestors of the node, starting from the root of the traversal and
+    /// ending with its parent.
+    ///
+    /// This is empty for the root itself.
+    pub parents: Vec<&'a Node>,
+}
+
+impl<'a> Visited<'a> {
+    /// The depth of the node, where the root of the traversal is at depth
+    /// `0`.
+    #[must_use]
+    pub const fn depth(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// The parents of the children of the node.
+    fn child_parents(&self) -> Vec<&'a Node> {
+        let mut parents = Vec::with_capacity(self.parents.len() + 1);
+        parents.extend_from_slice(&self.parents);
+        parents.push(self.node);
+        parents
+    }
+}
+
+/// A depth-first (pre-order) traversal of a [`Node`] tree.
+///
+/// Created by [`Node::dfs`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Dfs<'a> {
+    stack: Vec<Visited<'a>>,
+}
+
+impl<'a> Dfs<'a> {
+    pub(crate) fn new(root: &'a Node) -> Self {
+        Self {
+            stack: vec![Visited {
+                node: root,
+                parents: Vec::new(),
+            }],
+        }
+    }
+}
+
+impl<'a> Iterator for Dfs<'a> {
+    type Item = Visited<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let visited = self.stack.pop()?;
+
+        let children = visited
+            .node
+            .as_children()
+            .unwrap_or_default()
+            .iter()
+            .rev()
+            .map(|child| Visited {
+                node: child,
+                parents: visited.child_parents(),
+            });
+        self.stack.extend(children);
+
+        Some(visited)
+    }
+}
+
+/// A breadth-first (level-order) traversal of a [`Node`] tree.
+///
+/// Created by [`Node::bfs`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Bfs<'a> {
+    queue: VecDeque<Visited<'a>>,
+}
+
+impl<'a> Bfs<'a> {
+    pub(crate) fn new(root: &'a Node) -> Self {
+        Self {
+            queue: VecDeque::from([Visited {
+                node: root,
+                parents: Vec::new(),
+            }]),
+        }
+    }
+}
+
+impl<'a> Iterator for Bfs<'a> {
+    type Item = Visited<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let visited = self.queue.pop_front()?;
+
+        let children = visited.node.children_iter().map(|child| Visited {
+            node: child,
+            parents: visited.child_parents(),
+        });
+        self.queue.extend(children);
+
+        Some(visited)
+    }
+}
@@ -0,0 +1,286 @@
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use proc_macro2_diagnostics::{Diagnostic, SpanDiagnosticExt};
+use quote::{format_ident, quote, ToTokens};
+use syn::{spanned::Spanned, Expr, FnArg, GenericArgument, ItemFn, Meta, Pat, PathArguments, Type};
+
+/// A prop of a component, which is one of its function's arguments.
+struct Prop {
+    name: Ident,
+    kind: PropKind,
+    default: Option<Expr>,
+}
+
+enum PropKind {
+    /// An [`Option<T>`], which is [`None`] when the attribute isn't given.
+    Optional(Type),
+    /// A [`bool`], which is `true` when the attribute is given without a
+    /// value and `false` when it isn't given.
+    Flag,
+    /// Any other type, which must be given (unless it has a default).
+    Value(Type),
+}
+
+/// Turn a function into a component, for `typed::html!`.
+pub fn component(function: ItemFn) -> Result<TokenStream2, Diagnostic> {
+    let signature = &function.sig;
+
+    if let Some(asyncness) = signature.asyncness {
+        return Err(asyncness.span().error("components can't be async"));
+    }
+
+    if !signature.generics.params.is_empty() || signature.generics.where_clause.is_some() {
+        return Err(signature
+            .generics
+            .span()
+            .error("components can't be generic"));
+    }
+
+    let name = &signature.ident;
+    let visibility = &function.vis;
+    let attributes_name = format_ident!("{name}Attributes");
+
+    // the arguments are evaluated in `into_node`, so these mustn't shadow
+    // anything used in defaults.
+    let attributes = Ident::new("attributes", Span::mixed_site());
+    let extra_attributes = Ident::new("extra_attributes", Span::mixed_site());
+    let children = Ident::new("children", Span::mixed_site());
+
+    let mut inner_function = function.clone();
+    let mut props = Vec::new();
+    let mut arguments = Vec::new();
+
+    for argument in &mut inner_function.sig.inputs {
+        let FnArg::Typed(argument) = argument else {
+            return Err(argument.span().error("components can't take `self`"));
+        };
+
+        let Pat::Ident(pattern) = &*argument.pat else {
+            return Err(argument
+                .pat
+                .span()
+                .error("component props must be named with an identifier"));
+        };
+
+        let prop_name = pattern.ident.clone();
+
+        let default = take_default(&mut argument.attrs)?;
+
+        if prop_name == "children" {
+            arguments.push(quote! {
+                ::std::convert::Into::into(::html_node::Fragment {
+                    children: ::std::option::Option::unwrap_or_default(#children),
+                })
+            });
+            continue;
+        }
+
+        if prop_name == "extra_attributes" {
+            arguments.push(extra_attributes.to_token_stream());
+            continue;
+        }
+
+        let kind = match &*argument.ty {
+            ty if is_bool(ty) => PropKind::Flag,
+            ty => option_inner(ty).map_or_else(
+                || PropKind::Value(ty.clone()),
+                |inner| PropKind::Optional(inner.clone()),
+            ),
+        };
+
+        arguments.push(prop_argument(
+            name,
+            &attributes,
+            &prop_name,
+            &kind,
+            default.as_ref(),
+        ));
+
+        props.push(Prop {
+            name: prop_name,
+            kind,
+            default,
+        });
+    }
+
+    let prop_names = props.iter().map(|prop| &prop.name).collect::<Vec<_>>();
+    let prop_types = props.iter().map(|prop| match &prop.kind {
+        PropKind::Optional(ty) | PropKind::Value(ty) => ty.to_token_stream(),
+        PropKind::Flag => quote!(bool),
+    });
+
+    let required = props
+        .iter()
+        .filter(|prop| matches!(prop.kind, PropKind::Value(_)) && prop.default.is_none())
+        .map(|prop| {
+            let prop_name = prop.name.to_string();
+            let error = format!("`{name}` needs a value for the required attribute `{prop_name}`");
+
+            quote!((#prop_name, #error))
+        });
+
+    let documentation = function
+        .attrs
+        .iter()
+        .filter(|attribute| attribute.path().is_ident("doc"));
+
+    inner_function.vis = syn::Visibility::Inherited;
+
+    Ok(quote! {
+        #(#documentation)*
+        #visibility struct #name {
+            /// The props of the component.
+            #visibility attributes: #attributes_name,
+            /// The attributes which aren't props, like `data-*` attributes.
+            #visibility extra_attributes: ::std::vec::Vec<(
+                ::std::string::String,
+                ::std::option::Option<::std::string::String>,
+            )>,
+        }
+
+        #[doc = ::std::concat!("The props of [`", ::std::stringify!(#name), "`].")]
+        #[allow(missing_docs)]
+        #visibility struct #attributes_name {
+            #(#visibility #prop_names: ::html_node::typed::Attribute<#prop_types>,)*
+        }
+
+        impl ::std::default::Default for #attributes_name {
+            fn default() -> Self {
+                Self {
+                    #(#prop_names: ::html_node::typed::Attribute::Missing,)*
+                }
+            }
+        }
+
+        impl ::html_node::typed::TypedElement for #name {
+            type Attributes = #attributes_name;
+
+            const REQUIRED_ATTRIBUTES: &'static [(&'static str, &'static str)] = &[#(#required),*];
+
+            fn from_attributes(
+                attributes: Self::Attributes,
+                extra_attributes: ::std::vec::Vec<(
+                    ::std::string::String,
+                    ::std::option::Option<::std::string::String>,
+                )>,
+            ) -> Self {
+                Self { attributes, extra_attributes }
+            }
+
+            fn into_node(
+                self,
+                #children: ::std::option::Option<::std::vec::Vec<::html_node::Node>>,
+            ) -> ::html_node::Node {
+                #[allow(non_snake_case, clippy::too_many_arguments)]
+                #inner_function
+
+                let Self {
+                    attributes: #attributes,
+                    extra_attributes: #extra_attributes,
+                } = self;
+
+                ::html_node::render::Render::into_node(#name(#(#arguments),*))
+            }
+        }
+    })
+}
+
+/// Remove the `#[default]` (or `#[default = value]`) attribute of an
+/// argument, returning the default value.
+fn take_default(attributes: &mut Vec<syn::Attribute>) -> Result<Option<Expr>, Diagnostic> {
+    let Some(i) = attributes
+        .iter()
+        .position(|attribute| attribute.path().is_ident("default"))
+    else {
+        return Ok(None);
+    };
+
+    match attributes.remove(i).meta {
+        Meta::Path(_) => Ok(Some(syn::parse_quote!(::std::default::Default::default()))),
+        Meta::NameValue(name_value) => Ok(Some(name_value.value)),
+        Meta::List(list) => Err(list
+            .span()
+            .error("expected `#[default]` or `#[default = value]`")),
+    }
+}
+
+/// The argument for a prop, from the component's attributes.
+fn prop_argument(
+    component: &Ident,
+    attributes: &Ident,
+    name: &Ident,
+    kind: &PropKind,
+    default: Option<&Expr>,
+) -> TokenStream2 {
+    let attribute = quote!(#attributes.#name);
+
+    match (kind, default) {
+        (PropKind::Optional(_), default) => {
+            let default = default.map_or_else(
+                || quote!(::std::option::Option::None),
+                ToTokens::to_token_stream,
+            );
+
+            quote! {
+                match #attribute {
+                    ::html_node::typed::Attribute::Present(value) => ::std::option::Option::Some(value),
+                    _ => #default,
+                }
+            }
+        }
+        (PropKind::Flag, default) => {
+            let default = default.map_or_else(|| quote!(false), ToTokens::to_token_stream);
+
+            quote! {
+                match #attribute {
+                    ::html_node::typed::Attribute::Present(value) => value,
+                    ::html_node::typed::Attribute::Empty => true,
+                    ::html_node::typed::Attribute::Missing => #default,
+                }
+            }
+        }
+        (PropKind::Value(_), Some(default)) => quote! {
+            match #attribute {
+                ::html_node::typed::Attribute::Present(value) => value,
+                _ => #default,
+            }
+        },
+        (PropKind::Value(_), None) => {
+            // `typed::html!` already checks that required props have values, so
+            // this only panics for components built by hand.
+            let error = format!("`{component}` needs a value for the attribute `{name}`");
+
+            quote! {
+                match #attribute {
+                    ::html_node::typed::Attribute::Present(value) => value,
+                    _ => ::std::panic!(#error),
+                }
+            }
+        }
+    }
+}
+
+fn is_bool(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident("bool"))
+}
+
+/// The `T` in an `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    let segment = path.path.segments.last()?;
+
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+
+    match arguments.args.first()? {
+        GenericArgument::Type(inner) if arguments.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Delimiter, Group, Ident, Span, TokenStream as TokenStream2, TokenTree};
+use proc_macro2_diagnostics::{Diagnostic, SpanDiagnosticExt};
+use quote::quote;
+use syn::Type;
+
+use crate::html_inner;
+
+/// Lower the control flow (`@if`, `@for`, `@match` and `@let`) in the tokens
+/// of an `html!` macro into blocks, which can then be parsed as HTML.
+///
+/// The bodies of `@if`, `@for` and `@match` are parsed as HTML themselves, and
+/// the blocks evaluate to a [`Node`](html_node_core::Node). `@let` becomes a
+/// block of only a `let` statement, which is in scope for the nodes after it
+/// (see [`crate::tokenize_nodes`]).
+pub fn lower(
+    tokens: TokenStream2,
+    extensions: Option<&HashMap<Ident, Option<Type>>>,
+) -> (TokenStream2, Vec<Diagnostic>) {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+    let mut cursor = Cursor {
+        tokens: &tokens,
+        position: 0,
+    };
+
+    let mut lowered = TokenStream2::new();
+    let mut diagnostics = Vec::new();
+
+    while let Some(token) = cursor.peek() {
+        let Some(keyword) = cursor.keyword() else {
+            lowered.extend([token.clone()]);
+            cursor.position += 1;
+            continue;
+        };
+
+        let span = keyword.span();
+
+        let block = match keyword.to_string().as_str() {
+            "if" => lower_if(&mut cursor, extensions),
+            "for" => lower_for(&mut cursor, extensions),
+            "match" => lower_match(&mut cursor, extensions),
+            "let" => lower_let(&mut cursor),
+            "else" => {
+                cursor.take_keyword();
+                Err(span.error("`@else` without a preceding `@if`"))
+            }
+            _ => {
+                // anything else (like `@username`) is just text.
+                lowered.extend([token.clone()]);
+                cursor.position += 1;
+                continue;
+            }
+        };
+
+        match block {
+            Ok(block) => {
+                let mut group = Group::new(Delimiter::Brace, block);
+                group.set_span(span);
+                lowered.extend([TokenTree::Group(group)]);
+            }
+            // the rest is still lowered, to avoid unrelated errors after this
+            // one.
+            Err(diagnostic) => diagnostics.push(diagnostic),
+        }
+    }
+
+    (lowered, diagnostics)
+}
+
+/// `@if condition { ... } @else if condition { ... } @else { ... }`
+fn lower_if(
+    cursor: &mut Cursor<'_>,
+    extensions: Option<&HashMap<Ident, Option<Type>>>,
+) -> Result<TokenStream2, Diagnostic> {
+    let if_token = cursor.take_keyword();
+    lower_if_branch(if_token, cursor, extensions)
+}
+
+/// The rest of an `@if` (or `@else if`) after its `if`.
+fn lower_if_branch(
+    if_token: Ident,
+    cursor: &mut Cursor<'_>,
+    extensions: Option<&HashMap<Ident, Option<Type>>>,
+) -> Result<TokenStream2, Diagnostic> {
+    let (condition, body) = cursor.take_until_body(&if_token)?;
+    let body = html_inner(body.stream(), extensions);
+
+    let else_branch = match cursor.keyword() {
+        Some(keyword) if keyword == "else" => {
+            let else_token = cursor.take_keyword();
+
+            match cursor.peek() {
+                Some(TokenTree::Ident(if_token)) if if_token == "if" => {
+                    cursor.position += 1;
+                    let nested = lower_if_branch(if_token.clone(), cursor, extensions)?;
+
+                    quote!(#else_token #nested)
+                }
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                    let body = html_inner(group.stream(), extensions);
+                    cursor.position += 1;
+
+                    quote!(#else_token { #body })
+                }
+                _ => {
+                    return Err(else_token
+                        .span()
+                        .error("expected `if` or a `{ ... }` body after `@else`"))
+                }
+            }
+        }
+        _ => quote!(else { ::html_node::Node::EMPTY }),
+    };
+
+    Ok(quote!(#if_token #(#condition)* { #body } #else_branch))
+}
+
+/// `@for pattern in iterator { ... }`
+fn lower_for(
+    cursor: &mut Cursor<'_>,
+    extensions: Option<&HashMap<Ident, Option<Type>>>,
+) -> Result<TokenStream2, Diagnostic> {
+    let for_token = cursor.take_keyword();
+    let (header, body) = cursor.take_until_body(&for_token)?;
+    let body = html_inner(body.stream(), extensions);
+
+    // the binding mustn't shadow variables used in the body.
+    let children = Ident::new("children", Span::mixed_site());
+
+    Ok(quote! {
+        let mut #children = ::std::vec::Vec::new();
+
+        #for_token #(#header)* {
+            ::std::vec::Vec::push(&mut #children, #body);
+        }
+
+        ::html_node::Node::Fragment(::html_node::Fragment { children: #children })
+    })
+}
+
+/// `@match scrutinee { pattern => { ... } }`
+fn lower_match(
+    cursor: &mut Cursor<'_>,
+    extensions: Option<&HashMap<Ident, Option<Type>>>,
+) -> Result<TokenStream2, Diagnostic> {
+    let match_token = cursor.take_keyword();
+    let (scrutinee, arms) = cursor.take_until_body(&match_token)?;
+
+    let arm_tokens = arms.stream().into_iter().collect::<Vec<_>>();
+    let mut arms_cursor = Cursor {
+        tokens: &arm_tokens,
+        position: 0,
+    };
+
+    let mut lowered_arms = TokenStream2::new();
+
+    while arms_cursor.peek().is_some() {
+        let pattern = arms_cursor.take_until_fat_arrow().ok_or_else(|| {
+            arms.span()
+                .error("expected `=>` after a `@match` arm's pattern")
+        })?;
+
+        let body = match arms_cursor.peek() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                html_inner(group.stream(), extensions)
+            }
+            Some(token) => {
+                return Err(token
+                    .span()
+                    .error("expected a `{ ... }` body after `=>` in `@match` arms"))
+            }
+            None => {
+                return Err(arms
+                    .span_close()
+                    .error("expected a `{ ... }` body after `=>` in `@match` arms"))
+            }
+        };
+
+        arms_cursor.position += 1;
+
+        if matches!(arms_cursor.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == ',') {
+            arms_cursor.position += 1;
+        }
+
+        lowered_arms.extend(quote!(#(#pattern)* => #body,));
+    }
+
+    Ok(quote!(#match_token #(#scrutinee)* { #lowered_arms }))
+}
+
+/// `@let pattern = value;`
+fn lower_let(cursor: &mut Cursor<'_>) -> Result<TokenStream2, Diagnostic> {
+    let let_token = cursor.take_keyword();
+
+    let end = cursor.tokens[cursor.position..]
+        .iter()
+        .position(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ';'))
+        .ok_or_else(|| let_token.span().error("expected `;` after `@let` binding"))?;
+
+    let binding = &cursor.tokens[cursor.position..cursor.position + end];
+    cursor.position += end + 1;
+
+    Ok(quote!(#let_token #(#binding)*;))
+}
+
+struct Cursor<'t> {
+    tokens: &'t [TokenTree],
+    position: usize,
+}
+
+impl<'t> Cursor<'t> {
+    fn peek(&self) -> Option<&'t TokenTree> {
+        self.tokens.get(self.position)
+    }
+
+    /// The keyword after an `@` at the cursor, if there is one.
+    fn keyword(&self) -> Option<&'t Ident> {
+        match self.tokens.get(self.position..self.position + 2)? {
+            [TokenTree::Punct(at), TokenTree::Ident(keyword)] if at.as_char() == '@' => {
+                Some(keyword)
+            }
+            _ => None,
+        }
+    }
+
+    /// Take the `@` and keyword at the cursor, returning the keyword.
+    fn take_keyword(&mut self) -> Ident {
+        let keyword = self
+            .keyword()
+            .cloned()
+            .expect("there should be a keyword at the cursor");
+        self.position += 2;
+        keyword
+    }
+
+    /// Take the tokens up to (and including) a `{ ... }` body.
+    fn take_until_body(
+        &mut self,
+        keyword: &Ident,
+    ) -> Result<(&'t [TokenTree], &'t Group), Diagnostic> {
+        let tokens = &self.tokens[self.position..];
+
+        let end = tokens
+            .iter()
+            .position(|token| {
+                matches!(token, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace)
+            })
+            .ok_or_else(|| {
+                keyword
+                    .span()
+                    .error(format!("expected a `{{ ... }}` body after `@{keyword}`"))
+            })?;
+
+        let TokenTree::Group(body) = &tokens[end] else {
+            unreachable!("the body should be a group");
+        };
+
+        self.position += end + 1;
+
+        Ok((&tokens[..end], body))
+    }
+
+    /// Take the tokens up to (and including) a `=>`, returning the tokens
+    /// before it.
+    fn take_until_fat_arrow(&mut self) -> Option<&'t [TokenTree]> {
+        let tokens = &self.tokens[self.position..];
+
+        let end = tokens.windows(2).position(|window| {
+            matches!(
+                window,
+                [TokenTree::Punct(equals), TokenTree::Punct(greater_than)]
+                    if equals.as_char() == '='
+                        && equals.spacing() == proc_macro2::Spacing::Joint
+                        && greater_than.as_char() == '>'
+            )
+        })?;
+
+        self.position += end + 2;
+
+        Some(&tokens[..end])
+    }
+}
@@ -1,23 +1,31 @@
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+#[cfg(feature = "typed")]
+mod component;
+mod control_flow;
 mod node_handlers;
+mod style_map;
 
 use std::collections::{HashMap, HashSet};
 
+use html_node_core::{RAW_TEXT_ELEMENTS, VOID_ELEMENTS};
 use node_handlers::{
-    handle_block, handle_comment, handle_doctype, handle_element, handle_fragment, handle_raw_text,
-    handle_text,
+    handle_block, handle_comment, handle_doctype, handle_element, handle_fragment, handle_text,
+    handle_unquoted_text,
 };
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, TokenStream as TokenStream2};
 use proc_macro2_diagnostics::Diagnostic;
 use quote::quote;
-use rstml::{node::Node, Parser, ParserConfig};
-use syn::Type;
+use rstml::{
+    node::{Node, NodeBlock},
+    Parser, ParserConfig,
+};
+use syn::{Stmt, Type};
 
 #[proc_macro]
 pub fn html(tokens: TokenStream) -> TokenStream {
-    html_inner(tokens.into(), None)
+    html_inner(tokens.into(), None).into()
 }
 
 #[cfg(feature = "typed")]
@@ -32,6 +40,7 @@ pub fn typed_html(tokens: TokenStream) -> TokenStream {
     }
 
     #[derive(syn_derive::Parse)]
+    #[allow(clippy::large_enum_variant)]
     enum MaybeColonAndType {
         #[parse(peek = Token![:])]
         ColonAndType(ColonAndType),
@@ -87,29 +96,147 @@ pub fn typed_html(tokens: TokenStream) -> TokenStream {
     extensions.insert(Ident::new("data", proc_macro2::Span::call_site()), None);
     extensions.insert(Ident::new("aria", proc_macro2::Span::call_site()), None);
 
-    html_inner(options.tokens, Some(&extensions))
+    html_inner(options.tokens, Some(&extensions)).into()
+}
+
+/// Turn a function into a component, which can be used like an element in
+/// `typed::html!`.
+///
+/// The function's arguments are the component's props, which are given as
+/// attributes:
+///
+/// - [`Option`] props are [`None`] when the attribute isn't given.
+/// - [`bool`] props are `true` when the attribute is given without a value, and
+///   `false` when it isn't given.
+/// - Props with `#[default]` (or `#[default = value]`) use [`Default`] (or the
+///   value) when the attribute isn't given.
+/// - Any other props are required, which `typed::html!` checks at compile time.
+///
+/// An argument named `children` gets the component's children (as anything
+/// that a [`Fragment`](html_node_core::Fragment) converts into, like a
+/// [`Node`](html_node_core::Node)), and one named `extra_attributes` gets the
+/// attributes which aren't props (like `data-*` attributes), which can be
+/// spread onto an element with `{..extra_attributes}`.
+///
+/// The function can return anything which implements
+/// [`Render`](html_node_core::render::Render).
+///
+/// ```rust
+/// use html_node::{
+///     component,
+///     typed::{self, elements::*},
+///     Node,
+/// };
+///
+/// #[component]
+/// fn Card(
+///     title: String,
+///     subtitle: Option<String>,
+///     #[default = 2] level: u8,
+///     highlighted: bool,
+///     extra_attributes: Vec<(String, Option<String>)>,
+///     children: Node,
+/// ) -> Node {
+///     typed::html! {
+///         <div class=["card", ("highlighted", highlighted)] {..extra_attributes}>
+///             @if level == 2 {
+///                 <h2>{title}</h2>
+///             } @else {
+///                 <h3>{title}</h3>
+///             }
+///             {subtitle}
+///             {children}
+///         </div>
+///     }
+/// }
+///
+/// let html = typed::html! {
+///     <Card title="Groceries" highlighted data-id="1">
+///         <p>"Milk"</p>
+///     </Card>
+/// };
+///
+/// assert_eq!(
+///     html.to_string(),
+///     r#"<div class="card highlighted" data-id="1"><h2>Groceries</h2><p>Milk</p></div>"#,
+/// );
+/// ```
+///
+/// Leaving out a required prop fails to compile:
+///
+/// ```compile_fail
+/// use html_node::{
+///     component,
+///     typed::{self, elements::*},
+///     Node,
+/// };
+///
+/// #[component]
+/// fn Card(title: String) -> Node {
+///     typed::html! { <h2>{title}</h2> }
+/// }
+///
+/// let html = typed::html! { <Card></Card> };
+/// ```
+///
+/// And so does giving it without a value:
+///
+/// ```compile_fail
+/// use html_node::{
+///     component,
+///     typed::{self, elements::*},
+///     Node,
+/// };
+///
+/// #[component]
+/// fn Card(title: String) -> Node {
+///     typed::html! { <h2>{title}</h2> }
+/// }
+///
+/// let html = typed::html! { <Card title></Card> };
+/// ```
+#[cfg(feature = "typed")]
+#[proc_macro_attribute]
+pub fn component(arguments: TokenStream, item: TokenStream) -> TokenStream {
+    use proc_macro2_diagnostics::SpanDiagnosticExt;
+
+    let function = syn::parse_macro_input!(item as syn::ItemFn);
+
+    if !arguments.is_empty() {
+        let error = TokenStream2::from(arguments)
+            .into_iter()
+            .next()
+            .expect("the arguments shouldn't be empty")
+            .span()
+            .error("`#[component]` takes no arguments")
+            .emit_as_item_tokens();
+
+        return quote!(#error #function).into();
+    }
+
+    component::component(function.clone())
+        .unwrap_or_else(|diagnostic| {
+            let error = diagnostic.emit_as_item_tokens();
+            quote!(#error #function)
+        })
+        .into()
 }
 
 fn html_inner(
     tokens: TokenStream2,
     extensions: Option<&HashMap<Ident, Option<Type>>>,
-) -> TokenStream {
-    // from: https://html.spec.whatwg.org/dev/syntax.html#void-elements
-    let void_elements = [
-        "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source",
-        "track", "wbr",
-    ]
-    .into_iter()
-    .collect::<HashSet<_>>();
-
-    // from: https://html.spec.whatwg.org/dev/syntax.html#raw-text-elements
-    let raw_text_elements = ["script", "style"].into_iter().collect();
+) -> TokenStream2 {
+    let void_elements = VOID_ELEMENTS.iter().copied().collect::<HashSet<_>>();
+    let raw_text_elements = RAW_TEXT_ELEMENTS.iter().copied().collect();
 
     let config = ParserConfig::new()
         .recover_block(true)
         .always_self_closed_elements(void_elements.clone())
         .raw_text_elements(raw_text_elements);
 
+    let (tokens, control_flow_diagnostics) = control_flow::lower(tokens, extensions);
+    let (tokens, style_map_diagnostics) = style_map::lower(tokens);
+
     let parser = Parser::new(config);
     let (parsed_nodes, parsing_diagnostics) = parser.parse_recoverable(tokens).split_vec();
     let (tokenized_nodes, tokenization_diagnostics) =
@@ -128,8 +255,10 @@ fn html_inner(
         }
     };
 
-    let errors = parsing_diagnostics
+    let errors = control_flow_diagnostics
         .into_iter()
+        .chain(style_map_diagnostics)
+        .chain(parsing_diagnostics)
         .chain(tokenization_diagnostics)
         .map(Diagnostic::emit_as_expr_tokens);
 
@@ -139,7 +268,6 @@ fn html_inner(
             #node
         }
     }
-    .into()
 }
 
 fn tokenize_nodes(
@@ -147,20 +275,66 @@ fn tokenize_nodes(
     extensions: Option<&HashMap<Ident, Option<Type>>>,
     nodes: &[Node],
 ) -> (Vec<TokenStream2>, Vec<Diagnostic>) {
-    let (token_streams, diagnostics) = nodes
+    // `@let` bindings are in scope for the nodes after them, so those nodes
+    // are nested in the binding's block.
+    let binding = nodes.iter().enumerate().find_map(|(i, node)| match node {
+        Node::Block(block) => let_statements(block).map(|statements| (i, statements)),
+        _ => None,
+    });
+
+    let (nodes, scoped) = match binding {
+        Some((i, statements)) => {
+            let (scoped, scoped_diagnostics) =
+                tokenize_nodes(void_elements, extensions, &nodes[i + 1..]);
+
+            let scoped = quote! {
+                {
+                    #(#statements)*
+
+                    ::html_node::Node::Fragment(
+                        ::html_node::Fragment {
+                            children: ::std::vec![#(#scoped),*],
+                        }
+                    )
+                }
+            };
+
+            (&nodes[..i], Some((scoped, scoped_diagnostics)))
+        }
+        None => (nodes, None),
+    };
+
+    let (mut token_streams, diagnostics) = nodes
         .iter()
         .map(|node| match node {
             Node::Comment(comment) => (handle_comment(comment), vec![]),
-            Node::Doctype(doctype) => (handle_doctype(doctype), vec![]),
+            Node::Doctype(doctype) => handle_doctype(doctype),
             Node::Fragment(fragment) => handle_fragment(void_elements, extensions, fragment),
             Node::Element(element) => handle_element(void_elements, extensions, element),
             Node::Block(block) => (handle_block(block), vec![]),
             Node::Text(text) => (handle_text(text), vec![]),
-            Node::RawText(text) => (handle_raw_text(text), vec![]),
+            Node::RawText(text) => (handle_unquoted_text(text), vec![]),
         })
         .unzip::<_, _, Vec<_>, Vec<_>>();
 
-    let diagnostics = diagnostics.into_iter().flatten().collect();
+    let mut diagnostics = diagnostics.into_iter().flatten().collect::<Vec<_>>();
+
+    if let Some((scoped, scoped_diagnostics)) = scoped {
+        token_streams.push(scoped);
+        diagnostics.extend(scoped_diagnostics);
+    }
 
     (token_streams, diagnostics)
 }
+
+/// The statements of a block made only of `let` statements, which is what
+/// `@let` is lowered into.
+fn let_statements(block: &NodeBlock) -> Option<&[Stmt]> {
+    let statements = &block.try_block()?.stmts;
+
+    (!statements.is_empty()
+        && statements
+            .iter()
+            .all(|statement| matches!(statement, Stmt::Local(_))))
+    .then_some(statements.as_slice())
+}
@@ -3,14 +3,15 @@ mod typed;
 
 use std::collections::{HashMap, HashSet};
 
-use proc_macro2::{Ident, Literal, TokenStream as TokenStream2};
+use html_node_core::{is_raw_text_element, Doctype};
+use proc_macro2::{Ident, Literal, Span, TokenStream as TokenStream2};
 use proc_macro2_diagnostics::{Diagnostic, SpanDiagnosticExt};
 use quote::{quote, ToTokens};
 use rstml::node::{
-    KeyedAttribute, NodeAttribute, NodeBlock, NodeComment, NodeDoctype, NodeElement, NodeFragment,
-    NodeName, NodeText, RawText,
+    KeyedAttribute, Node, NodeAttribute, NodeBlock, NodeComment, NodeDoctype, NodeElement,
+    NodeFragment, NodeName, NodeText, RawText,
 };
-use syn::{spanned::Spanned, Expr, ExprCast, Type};
+use syn::{spanned::Spanned, Expr, ExprArray, ExprRange, RangeLimits, Stmt, Type};
 
 use crate::tokenize_nodes;
 
@@ -26,16 +27,30 @@ pub fn handle_comment(comment: &NodeComment) -> TokenStream2 {
     }
 }
 
-pub fn handle_doctype(doctype: &NodeDoctype) -> TokenStream2 {
-    let syntax = &doctype.value.to_token_stream_string();
+pub fn handle_doctype(doctype: &NodeDoctype) -> (TokenStream2, Vec<Diagnostic>) {
+    let syntax = doctype.value.to_token_stream_string();
+
+    // the token stream string can have odd spacing, so write it in its
+    // canonical form instead.
+    let (syntax, diagnostics) = match Doctype::from(syntax).normalize() {
+        Ok(doctype) => (doctype.syntax.into_owned(), vec![]),
+        Err(error) => (
+            String::new(),
+            vec![doctype
+                .value
+                .span()
+                .error(format!("invalid doctype: {error}"))],
+        ),
+    };
 
-    quote! {
-        ::html_node::Node::Doctype(
-            ::html_node::Doctype {
-                syntax: ::std::convert::Into::<::std::string::String>::into(#syntax),
-            }
-        )
-    }
+    (
+        quote! {
+            ::html_node::Node::Doctype(
+                ::std::convert::Into::<::html_node::Doctype>::into(#syntax)
+            )
+        },
+        diagnostics,
+    )
 }
 
 pub fn handle_fragment(
@@ -75,17 +90,30 @@ pub fn handle_element_untyped(
     void_elements: &HashSet<&str>,
     element: &NodeElement,
 ) -> (TokenStream2, Vec<Diagnostic>) {
+    // the binding mustn't shadow variables used in the values.
+    let binding = Ident::new("attributes", Span::mixed_site());
+
+    // each attribute is a statement which adds it to the binding, since
+    // attributes can be left out or spread.
     handle_element_inner(
         |block| {
-            let attribute_tokens = quote! {
-                (
-                    ::std::convert::Into::<::std::string::String>::into(
-                        #[allow(unused_braces)]
-                        #block,
-                    ),
-                    ::std::option::Option::None,
-                )
-            };
+            let attribute_tokens = spread_attributes(block).map_or_else(
+                || {
+                    quote! {
+                        ::std::vec::Vec::push(
+                            &mut #binding,
+                            (
+                                ::std::convert::Into::<::std::string::String>::into(
+                                    #[allow(unused_braces)]
+                                    #block,
+                                ),
+                                ::std::option::Option::None,
+                            ),
+                        );
+                    }
+                },
+                |attributes| quote!(::html_node::spread_attributes(&mut #binding, #attributes);),
+            );
 
             (attribute_tokens, None)
         },
@@ -94,39 +122,67 @@ pub fn handle_element_untyped(
 
             let key = quote!(::std::convert::Into::<::std::string::String>::into(#key));
 
-            let value = attribute.value().map(|value| match value {
-                Expr::Cast(ExprCast { expr, ty, .. }) => (&**expr, Some(ty)),
-                _ => (value, None),
-            });
-
-            let attribute_tokens = value.map_or_else(
-                || quote!((#key, ::std::option::Option::None)),
-                |(value, ty)| ty.map_or_else(
-                    || quote!{
-                        (
-                            #key,
-                            ::std::option::Option::Some(::std::string::ToString::to_string(&#value)),
-                        )
-                    },
-                    |ty| quote!{
-                        (
-                            #key,
-                            ::std::option::Option::<#ty>::from(#value).map(|v| ::std::string::ToString::to_string(&v)),
-                        )
-                    },
-                ),
+            let value =
+                class_list(attribute).or_else(|| attribute.value().map(ToTokens::to_token_stream));
+
+            // values which don't implement `IntoAttributeValue` can still be
+            // converted with `ToString` (see `html_node::render::Block`).
+            let attribute = value.map_or_else(
+                || quote!(::std::option::Option::Some((#key, ::std::option::Option::None))),
+                |value| {
+                    // the binding mustn't shadow variables used in the value.
+                    let key_binding = Ident::new("key", Span::mixed_site());
+
+                    quote! {
+                        {
+                            #[allow(unused_imports)]
+                            use ::html_node::render::{
+                                AttributeValueBlock as _,
+                                ToStringAttributeValueBlock as _,
+                            };
+
+                            let #key_binding = #key;
+
+                            (&::html_node::render::Block::new(
+                                #[allow(unused_braces)]
+                                #value,
+                            ))
+                                .take_attribute_value(&#key_binding)
+                                .map(|value| (#key_binding, value))
+                        }
+                    }
+                },
             );
 
-            (attribute_tokens, None)
+            (
+                quote!(::std::iter::Extend::extend(&mut #binding, #attribute);),
+                None,
+            )
         },
         |element, attributes, children| {
             let name = node_name_to_literal(element.name());
 
+            let attributes = if attributes.is_empty() {
+                quote!(::std::vec::Vec::new())
+            } else {
+                quote! {
+                    {
+                        let mut #binding = ::std::vec::Vec::<(
+                            ::std::string::String,
+                            ::std::option::Option<::std::string::String>,
+                        )>::new();
+                        #(#attributes)*
+
+                        #binding
+                    }
+                }
+            };
+
             quote! {
                 ::html_node::Node::Element(
                     ::html_node::Element {
                         name: ::std::convert::Into::<::std::string::String>::into(#name),
-                        attributes: ::std::vec![#(#attributes),*],
+                        attributes: #attributes,
                         children: #children,
                     }
                 )
@@ -138,6 +194,45 @@ pub fn handle_element_untyped(
     )
 }
 
+/// The [`Classes`](html_node_core::Classes) builder for a `class=[...]`
+/// attribute, if the attribute is one.
+fn class_list(attribute: &KeyedAttribute) -> Option<TokenStream2> {
+    let Some(Expr::Array(ExprArray { elems, .. })) = attribute.value() else {
+        return None;
+    };
+
+    let elems = elems.iter();
+
+    (attribute.key.to_string() == "class")
+        .then(|| quote!(::html_node::Classes::new() #(.with(#elems))*))
+}
+
+/// The attributes spread from a block attribute like `{..attributes}` (which
+/// can be anything that implements `IntoIterator<Item = (K, Option<V>)>`), if
+/// the block is one.
+fn spread_attributes(block: &NodeBlock) -> Option<TokenStream2> {
+    let block = block.try_block()?;
+
+    let [Stmt::Expr(
+        Expr::Range(ExprRange {
+            attrs,
+            start: None,
+            limits: RangeLimits::HalfOpen(_),
+            end: Some(attributes),
+        }),
+        None,
+    )] = block.stmts.as_slice()
+    else {
+        return None;
+    };
+
+    if !attrs.is_empty() {
+        return None;
+    }
+
+    Some(attributes.to_token_stream())
+}
+
 fn handle_element_inner<T>(
     handle_block: impl Fn(&NodeBlock) -> (T, Option<Diagnostic>),
     handle_keyed: impl Fn(&KeyedAttribute) -> (T, Option<Diagnostic>),
@@ -155,7 +250,8 @@ fn handle_element_inner<T>(
         })
         .unzip::<_, _, Vec<_>, Vec<_>>();
 
-    let is_void_element = void_elements.contains(element.open_tag.name.to_string().as_str());
+    let name = element.open_tag.name.to_string();
+    let is_void_element = void_elements.contains(name.as_str());
 
     let (children, void_diagnostics) = if is_void_element {
         let diagnostic = if element.children.is_empty() {
@@ -167,6 +263,24 @@ fn handle_element_inner<T>(
         };
 
         (quote!(::std::option::Option::None), diagnostic)
+    } else if is_raw_text_element(&name) {
+        let (inner_nodes, inner_diagnostics) = element
+            .children
+            .iter()
+            .map(|child| match child {
+                Node::RawText(raw_text) => (handle_raw_text(raw_text), vec![]),
+                other => {
+                    let (mut nodes, diagnostics) =
+                        tokenize_nodes(void_elements, extensions, std::slice::from_ref(other));
+                    (nodes.remove(0), diagnostics)
+                }
+            })
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+
+        (
+            quote!(::std::option::Option::Some(::std::vec![#(#inner_nodes),*])),
+            inner_diagnostics.into_iter().flatten().collect(),
+        )
     } else {
         let (inner_nodes, inner_diagnostics) =
             tokenize_nodes(void_elements, extensions, &element.children);
@@ -189,8 +303,15 @@ fn handle_element_inner<T>(
 }
 
 pub fn handle_block(block: &NodeBlock) -> TokenStream2 {
+    // values which don't implement `Render` can still be converted with
+    // `Into<Node>` (see `html_node::render::Block`).
     quote! {
-        ::std::convert::Into::<::html_node::Node>::into(#[allow(unused_braces)] #block)
+        {
+            #[allow(unused_imports)]
+            use ::html_node::render::{IntoNodeBlock as _, RenderBlock as _};
+
+            (&::html_node::render::Block::new(#[allow(unused_braces)] #block)).take_node()
+        }
     }
 }
 
@@ -206,10 +327,8 @@ pub fn handle_text(text: &NodeText) -> TokenStream2 {
     }
 }
 
-pub fn handle_raw_text(raw_text: &RawText) -> TokenStream2 {
-    let tokens = raw_text.to_string_best();
-    let mut text = Literal::string(&tokens);
-    text.set_span(raw_text.span());
+pub fn handle_unquoted_text(raw_text: &RawText) -> TokenStream2 {
+    let text = raw_text_to_literal(raw_text);
 
     quote! {
         ::html_node::Node::Text(
@@ -220,6 +339,25 @@ pub fn handle_raw_text(raw_text: &RawText) -> TokenStream2 {
     }
 }
 
+/// The contents of a raw text element (like `<script>`), which aren't escaped.
+pub fn handle_raw_text(raw_text: &RawText) -> TokenStream2 {
+    let text = raw_text_to_literal(raw_text);
+
+    quote! {
+        ::html_node::Node::RawText(
+            ::html_node::RawText {
+                text: ::std::convert::Into::<::std::string::String>::into(#text),
+            }
+        )
+    }
+}
+
+fn raw_text_to_literal(raw_text: &RawText) -> Literal {
+    let mut text = Literal::string(&raw_text.to_string_best());
+    text.set_span(raw_text.span());
+    text
+}
+
 fn node_name_to_literal(node_name: &NodeName) -> TokenStream2 {
     match node_name {
         NodeName::Block(block) => quote!(#[allow(unused_braces)] #block),
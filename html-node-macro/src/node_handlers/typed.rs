@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 
 use proc_macro2::{Ident, Punct, Span, TokenStream as TokenStream2};
 use proc_macro2_diagnostics::{Diagnostic, SpanDiagnosticExt};
-use quote::{quote, ToTokens};
+use quote::{quote, quote_spanned, ToTokens};
 use rstml::node::{KeyedAttribute, NodeElement, NodeName, NodeNameFragment};
 use syn::{
     punctuated::{Pair, Punctuated},
@@ -10,8 +10,9 @@ use syn::{
     ExprPath, Type,
 };
 
-use super::{handle_element_inner, node_name_to_literal};
+use super::{class_list, handle_element_inner, node_name_to_literal, spread_attributes};
 
+#[allow(clippy::large_enum_variant)]
 enum AttrType {
     Component,
     TypeChecked {
@@ -23,6 +24,7 @@ enum AttrType {
         key: TokenStream2,
         value: TokenStream2,
     },
+    Spread(TokenStream2),
 }
 
 #[allow(clippy::too_many_lines)]
@@ -33,16 +35,22 @@ pub fn handle_element(
 ) -> (TokenStream2, Vec<Diagnostic>) {
     handle_element_inner(
         |block| {
-            let diagnostic = block
-                .span()
-                .error("typed elements don't support block attributes");
+            spread_attributes(block).map_or_else(
+                || {
+                    let diagnostic = block.span().error(
+                        "typed elements only support spreading block attributes, like \
+                         `{..attributes}`",
+                    );
 
-            (
-                AttrType::TypeChecked {
-                    key: TokenStream2::new(),
-                    value: None,
+                    (
+                        AttrType::TypeChecked {
+                            key: TokenStream2::new(),
+                            value: None,
+                        },
+                        Some(diagnostic),
+                    )
                 },
-                Some(diagnostic),
+                |attributes| (AttrType::Spread(attributes), None),
             )
         },
         |attr| handle_attribute(attr, extensions),
@@ -54,14 +62,17 @@ pub fn handle_element(
                 (type_checked_keys, type_checked_values),
                 (other_keys, other_values),
                 extensions,
+                spreads,
             ) = attributes.into_iter().fold(
                 (
                     false,
                     (Vec::new(), Vec::new()),
                     (Vec::new(), Vec::new()),
                     HashMap::<_, (Vec<_>, Vec<_>)>::new(),
+                    Vec::new(),
                 ),
-                |(mut component, mut type_checked, mut other, mut extension), attribute| {
+                |(mut component, mut type_checked, mut other, mut extension, mut spreads),
+                 attribute| {
                     match attribute {
                         AttrType::Component => component = true,
                         AttrType::TypeChecked { key, value } => {
@@ -83,9 +94,10 @@ pub fn handle_element(
                                 other.1.push(value);
                             }
                         }
+                        AttrType::Spread(attributes) => spreads.push(attributes),
                     }
 
-                    (component, type_checked, other, extension)
+                    (component, type_checked, other, extension, spreads)
                 },
             );
 
@@ -101,15 +113,21 @@ pub fn handle_element(
                 }
             });
 
+            // the binding mustn't shadow variables used in the values.
+            let v = Ident::new("v", Span::mixed_site());
+
             let extensions = quote! {
                 {
-                    let mut v = ::std::vec::Vec::new();
+                    let mut #v = ::std::vec::Vec::new();
                     #(
-                        v.append(&mut #extensions);
+                        #v.append(&mut #extensions);
+                    )*
+                    #v.append(&mut ::std::vec![#((#other_keys, #other_values),)*]);
+                    #(
+                        ::html_node::spread_attributes(&mut #v, #spreads);
                     )*
-                    v.append(&mut ::std::vec![#((#other_keys, #other_values),)*]);
 
-                    v
+                    #v
                 }
             };
 
@@ -121,6 +139,31 @@ pub fn handle_element(
                 }
             };
 
+            // components made with `typed::component!` take all of their
+            // attributes, so only other elements can have required ones.
+            let required_check = if component {
+                TokenStream2::new()
+            } else {
+                // attributes without a value (like `<Card title>`) don't count,
+                // since they can't give a required attribute its value.
+                let given = type_checked_keys
+                    .iter()
+                    .zip(&type_checked_values)
+                    .filter(|(_, value)| value.is_some())
+                    .map(|(key, _)| {
+                        let key = key.to_string();
+                        key.strip_prefix("r#").unwrap_or(&key).to_owned()
+                    })
+                    .collect::<Vec<_>>();
+
+                quote_spanned! {name.span()=>
+                    const _: () = ::html_node::typed::check_required_attributes(
+                        <#name as ::html_node::typed::TypedElement>::REQUIRED_ATTRIBUTES,
+                        &[#(#given),*],
+                    );
+                }
+            };
+
             let type_checked_values = if component {
                 Box::new(type_checked_values.into_iter().map(|value| {
                     quote! {
@@ -129,16 +172,22 @@ pub fn handle_element(
                 })) as Box<dyn Iterator<Item = _>>
             } else {
                 Box::new(type_checked_values.into_iter().map(|value| {
-                    quote! {
-                        ::html_node::typed::Attribute::Present(
-                            #value
-                        )
-                    }
+                    value.map_or_else(
+                        || quote!(::html_node::typed::Attribute::Empty),
+                        |value| {
+                            quote! {
+                                ::html_node::typed::Attribute::Present(
+                                    #value
+                                )
+                            }
+                        },
+                    )
                 })) as Box<dyn Iterator<Item = _>>
             };
 
             quote! {
                 {
+                    #required_check
                     type ElementAttributes = <#name as ::html_node::typed::TypedElement>::Attributes;
                     <#name as ::html_node::typed::TypedElement>::into_node(
                         <#name as ::html_node::typed::TypedElement>::from_attributes(
@@ -188,11 +237,11 @@ fn handle_attribute(
     };
 
     let attribute = match attr {
-        AttrType::Component => AttrType::Component,
+        attribute @ (AttrType::Component | AttrType::Spread(_)) => attribute,
         AttrType::TypeChecked { key, .. } => {
-            let value = attribute
-                .value()
-                .map(|value| quote!(::std::convert::Into::into(#value)));
+            let value = class_list(attribute)
+                .or_else(|| attribute.value().map(ToTokens::to_token_stream))
+                .map(|value| quote!(::std::convert::Into::into(#[allow(unused_braces)] #value)));
 
             AttrType::TypeChecked { key, value }
         }
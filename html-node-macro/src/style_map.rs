@@ -0,0 +1,84 @@
+use proc_macro2::{Delimiter, Group, Spacing, TokenStream as TokenStream2, TokenTree};
+use proc_macro2_diagnostics::{Diagnostic, SpanDiagnosticExt};
+use quote::quote;
+use syn::LitStr;
+
+/// Lower style maps (like `style={ {"color": color, "display": (hidden,
+/// "none")} }`) in the tokens of an `html!` macro into
+/// [`Style`](html_node_core::Style) builders, since they aren't valid Rust.
+pub fn lower(tokens: TokenStream2) -> (TokenStream2, Vec<Diagnostic>) {
+    let mut lowered = TokenStream2::new();
+    let mut diagnostics = Vec::new();
+    let mut is_value = false;
+
+    for token in tokens {
+        let token = match token {
+            TokenTree::Group(block) if is_value && block.delimiter() == Delimiter::Brace => {
+                match style_map(&block) {
+                    Some(Ok(style)) => {
+                        let mut lowered_block = Group::new(Delimiter::Brace, style);
+                        lowered_block.set_span(block.span());
+                        TokenTree::Group(lowered_block)
+                    }
+                    Some(Err(diagnostic)) => {
+                        diagnostics.push(diagnostic);
+                        TokenTree::Group(block)
+                    }
+                    None => TokenTree::Group(block),
+                }
+            }
+            token => token,
+        };
+
+        is_value = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '=');
+        lowered.extend([token]);
+    }
+
+    (lowered, diagnostics)
+}
+
+/// The [`Style`](html_node_core::Style) builder for a block with only a style
+/// map in it, if it is one.
+fn style_map(block: &Group) -> Option<Result<TokenStream2, Diagnostic>> {
+    let [TokenTree::Group(map)] = &*block.stream().into_iter().collect::<Vec<_>>() else {
+        return None;
+    };
+
+    if map.delimiter() != Delimiter::Brace {
+        return None;
+    }
+
+    let tokens = map.stream().into_iter().collect::<Vec<_>>();
+
+    // a string followed by a colon is never valid Rust, so this can only be a
+    // style map.
+    match tokens.as_slice() {
+        [TokenTree::Literal(_), TokenTree::Punct(colon), ..]
+            if colon.as_char() == ':' && colon.spacing() == Spacing::Alone => {}
+        _ => return None,
+    }
+
+    let declarations = tokens
+        .split(|token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ','))
+        .filter(|declaration| !declaration.is_empty())
+        .map(|declaration| match declaration {
+            [property, TokenTree::Punct(colon), value @ ..]
+                if colon.as_char() == ':' && !value.is_empty() =>
+            {
+                let property = syn::parse2::<LitStr>(property.clone().into()).map_err(|_| {
+                    property
+                        .span()
+                        .error("style map properties must be string literals")
+                })?;
+
+                Ok(quote!(.with(#property, #(#value)*)))
+            }
+            [first, ..] => Err(first
+                .span()
+                .error("expected `\"property\": value` in style map")),
+            [] => unreachable!("empty declarations should be skipped"),
+        })
+        .collect::<Result<Vec<_>, _>>();
+
+    Some(declarations.map(|declarations| quote!(::html_node::Style::new() #(#declarations)*)))
+}
@@ -1,14 +1,28 @@
 //! A HTML to [`Node`] macro powered by [rstml](https://github.com/rs-tml/rstml).
 //!
 //! Values returned from braced blocks (`{ ... }`) are expected to return
-//! something that implements [`Into<Node>`]. This is already implemented for
-//! anything that implements [`IntoIterator<Item = Node>`](IntoIterator), so you
-//! can return something like a [`Vec<Node>`] or an
-//! [`Iterator<Item = Node>`](Iterator) directly.
-//!
-//! Due to Rust's trait implementation rules, you cannot directly return
-//! [`String`]s. Instead, you can use the [`text!`] macro to convert the
-//! [`String`] to a [`Node::Text`].
+//! something that implements [`Render`](render::Render). This is already
+//! implemented for nodes, strings (which are escaped), numbers, [`Option`]s,
+//! [`Result`]s, [`Vec`]s and common iterators, so you can return something
+//! like a [`String`], a [`Vec<Node>`] or an [`Iterator<Item = Node>`](Iterator)
+//! directly. Values which don't implement it, like other iterators or your
+//! own types, are converted with [`Into<Node>`] instead.
+//!
+//! Attribute values (`key={ ... }`) are converted with [`IntoAttributeValue`],
+//! so `disabled={true}` gives a `disabled` attribute with no value, and
+//! `title={None::<&str>}` (or `hidden={false}`) leaves the attribute out.
+//! Enumerated attributes like `draggable={false}` and `aria-hidden={true}` get
+//! `"false"` and `"true"` instead, and values which don't implement it are
+//! converted with [`ToString`]. Attributes can also be spread onto an element
+//! from anything that implements `IntoIterator<Item = (K, Option<V>)>`, with
+//! `{..attributes}`. Spread attributes replace the ones with the same name
+//! before them, except for `class`, whose classes are added instead (see
+//! [`Element::spread_attrs`]).
+//!
+//! `class=["btn", ("active", is_active), maybe_class]` builds a deduplicated
+//! [`Classes`] list (like the [`classes!`] macro), and
+//! `style={ {"color": color, "display": (hidden, "none")} }` builds an escaped
+//! [`Style`] declaration list. Both leave the attribute out when they're empty.
 //!
 //! [`Node`] implements [`Display`][std::fmt::Display] (and by extension
 //! [`ToString`]), so you can turn it into a string representation easily using
@@ -65,6 +79,46 @@
 //! assert_eq!(html.to_string(), expected);
 //! ```
 //!
+//! ## Control Flow
+//!
+//! `@if` (with `@else if` and `@else`), `@for`, `@match` and `@let` work like
+//! their Rust counterparts, but their bodies are HTML. Bindings from `@let` are
+//! in scope for the rest of the nodes around them.
+//!
+//! ```rust
+//! use html_node::html;
+//!
+//! let shopping_list = vec![("milk", 2), ("eggs", 12), ("bread", 0)];
+//!
+//! let html = html! {
+//!     <ul>
+//!         @for &(item, count) in &shopping_list {
+//!             @let id = format!("item-{item}");
+//!             <li id={id}>
+//!                 @match count {
+//!                     0 => { <s>{item}</s> }
+//!                     1 => { {item} }
+//!                     count => { {item} " (" {count} ")" }
+//!                 }
+//!             </li>
+//!         }
+//!     </ul>
+//!     @if shopping_list.is_empty() {
+//!         <p>"Nothing to buy!"</p>
+//!     }
+//! };
+//!
+//! let expected = "\
+//! <ul>\
+//!     <li id=\"item-milk\">milk (2)</li>\
+//!     <li id=\"item-eggs\">eggs (12)</li>\
+//!     <li id=\"item-bread\"><s>bread</s></li>\
+//! </ul>\
+//! ";
+//!
+//! assert_eq!(html.to_string(), expected);
+//! ```
+//!
 //! ## Pretty-Printing
 //!
 //! Pretty-printing is supported by default when formatting a [`Node`] using the
@@ -73,6 +127,9 @@
 //! If you want to avoid specifying the alternate formatter, enabling the
 //! `pretty` feature will provide a convenience method [`Node::pretty()`] that
 //! returns a wrapper around the node that will always be pretty-printed.
+//! `Node::pretty_with()` takes a `pretty::PrettyConfig` to change the
+//! indentation and line width, and to keep inline elements (like `<a>` and
+//! `<strong>`) on the same line as the text around them.
 //!
 //! ```rust
 //! use html_node::{html, text};
@@ -134,12 +191,24 @@ mod macros;
 #[cfg(feature = "typed")]
 pub mod typed;
 
+#[cfg(feature = "parse")]
+pub use html_node_core::parse;
 #[cfg(feature = "pretty")]
 pub use html_node_core::pretty;
-pub use html_node_core::{Comment, Doctype, Element, Fragment, Node, Text, UnsafeText};
+#[cfg(feature = "sanitize")]
+pub use html_node_core::sanitize;
+#[doc(hidden)]
+pub use html_node_core::spread_attributes;
+pub use html_node_core::{
+    el, escape, is_escapable_raw_text_element, is_raw_text_element, is_void_element, render,
+    select, visit, Cdata, Classes, Comment, CommentError, CommentErrorKind, Doctype, DoctypeError,
+    DoctypeErrorKind, Element, Fragment, IntoAttributeValue, IntoStyleValue, Lazy, Node,
+    ProcessingInstruction, RawText, Style, Text, UnsafeText, ESCAPABLE_RAW_TEXT_ELEMENTS,
+    RAW_TEXT_ELEMENTS, VOID_ELEMENTS,
+};
+#[cfg(feature = "typed")]
+pub use html_node_macro::component;
 /// The HTML to [`Node`] macro.
 ///
 /// See the [crate-level documentation](crate) for more information.
 pub use html_node_macro::html;
-
-pub use self::macros::*;
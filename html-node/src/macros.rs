@@ -8,6 +8,58 @@ macro_rules! comment {
     };
 }
 
+/// Creates a [`Node::Cdata`][crate::Node::Cdata].
+///
+/// `html!` has no syntax for CDATA sections, so use this in a block instead.
+#[macro_export]
+macro_rules! cdata {
+    ($($tt:tt)*) => {
+        ::html_node::Node::Cdata(::html_node::Cdata {
+            text: ::std::format!($($tt)*),
+        })
+    };
+}
+
+/// Creates a [`Node::ProcessingInstruction`][crate::Node::ProcessingInstruction]
+/// from a target and (optionally) formatted data.
+///
+/// `html!` has no syntax for processing instructions, so use this in a block
+/// instead.
+#[macro_export]
+macro_rules! processing_instruction {
+    ($target:expr) => {
+        ::html_node::Node::ProcessingInstruction(::html_node::ProcessingInstruction {
+            target: ::std::convert::Into::<::std::string::String>::into($target),
+            data: ::std::string::String::new(),
+        })
+    };
+    ($target:expr, $($tt:tt)*) => {
+        ::html_node::Node::ProcessingInstruction(::html_node::ProcessingInstruction {
+            target: ::std::convert::Into::<::std::string::String>::into($target),
+            data: ::std::format!($($tt)*),
+        })
+    };
+}
+
+/// Creates a [`Classes`][crate::Classes] list, like `class=[...]` in `html!`.
+///
+/// ```rust
+/// use html_node::classes;
+///
+/// let is_active = false;
+///
+/// assert_eq!(
+///     classes!["btn", ("active", is_active), Some("btn-large")].to_string(),
+///     "btn btn-large",
+/// );
+/// ```
+#[macro_export]
+macro_rules! classes {
+    ($($classes:expr),* $(,)?) => {
+        ::html_node::Classes::new()$(.with($classes))*
+    };
+}
+
 /// Creates a [`Node::Text`][crate::Node::Text].
 #[macro_export]
 macro_rules! text {
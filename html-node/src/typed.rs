@@ -66,6 +66,8 @@
 //!     ",
 //! );
 
+#[doc(hidden)]
+pub use html_node_core::typed::check_required_attributes;
 #[allow(clippy::module_name_repetitions)]
 pub use html_node_core::typed::{elements, Attribute, TypedAttributes, TypedElement};
 /// Make a typed set of HTML attributes.
@@ -1,4 +1,6 @@
-use html_node::{html, text};
+#![allow(missing_docs)]
+
+use html_node::{html, text, Element, UnsafeText};
 
 #[test]
 fn basic() {
@@ -124,3 +126,1067 @@ fn pretty_printed_helper() {
 </div>"#;
     assert_eq!(expected, pretty_html.to_string());
 }
+
+#[test]
+fn write_to_matches_display() {
+    let html = html! {
+        <div id="one">
+            <p>"line one\nline two"</p>
+            <>
+                <br>
+                "tail"
+            </>
+        </div>
+    };
+
+    let mut compact = Vec::new();
+    html.write_to(&mut compact).unwrap();
+    assert_eq!(String::from_utf8(compact).unwrap(), html.to_string());
+
+    let mut pretty = Vec::new();
+    html.write_pretty_to(&mut pretty).unwrap();
+    assert_eq!(String::from_utf8(pretty).unwrap(), format!("{html:#}"));
+
+    let buffered = html.write_buffered(Vec::new()).unwrap();
+    assert_eq!(String::from_utf8(buffered).unwrap(), html.to_string());
+}
+
+#[test]
+fn pretty_printed_multiline_text() {
+    let html = html! {
+        <div>
+            {text!("a\r\n\nb\r")}
+        </div>
+    };
+
+    assert_eq!(format!("{html:#}"), "<div>\n    a\n    \n    b\r\n</div>");
+}
+
+#[test]
+fn pretty_printed_adjacent_text() {
+    let html = html! {
+        <div>{text!("a")}{text!("b")}</div>
+    };
+
+    assert_eq!(format!("{html:#}"), "<div>\n    a\n    b\n</div>");
+}
+
+#[cfg(feature = "pretty")]
+#[test]
+fn pretty_printed_inline_aware() {
+    use html_node::pretty::PrettyConfig;
+
+    let html = html! {
+        <div>
+            <h1>"Shopping List"</h1>
+            <p>
+                "A "<a href="/list">"link"</a>" and "<strong>"strong"</strong>" text."
+            </p>
+            <p>
+                "This paragraph is long enough that it has to be wrapped onto more than one line, but only at existing whitespace."
+            </p>
+        </div>
+    }
+    .pretty_with(PrettyConfig {
+        indent: "  ".into(),
+        ..PrettyConfig::inline_aware()
+    });
+
+    let expected = r#"<div>
+  <h1>Shopping List</h1>
+  <p>A <a href="/list">link</a> and <strong>strong</strong> text.</p>
+  <p>
+    This paragraph is long enough that it has to be wrapped onto more than one
+    line, but only at existing whitespace.
+  </p>
+</div>"#;
+
+    assert_eq!(html.to_string(), expected);
+}
+
+#[test]
+fn pretty_printed_preformatted() {
+    let script = Element {
+        name: "script".into(),
+        attributes: Vec::new(),
+        children: Some(vec![UnsafeText::from("const s = `a\nb`;").into()]),
+    };
+
+    let html = html! {
+        <div>
+            <pre>{text!("fn main() {{\n    println!(\"hi\");\n}}")}</pre>
+            <textarea>{text!("line one\n  line two")}</textarea>
+            {script}
+        </div>
+    };
+
+    let expected = "\
+<div>
+    <pre>fn main() {
+    println!(\"hi\");
+}</pre>
+    <textarea>line one
+  line two</textarea>
+    <script>const s = `a
+b`;</script>
+</div>";
+
+    assert_eq!(format!("{html:#}"), expected);
+}
+
+#[test]
+fn escaping_url_attributes() {
+    let safe = "https://example.com/?a=1&b=2";
+    let relative = "/about";
+    let evil = " JavaScript:alert(1)";
+
+    let html = html! {
+        <a href={safe}>safe</a>
+        <a href={relative}>relative</a>
+        <a href={evil} title={evil}>evil</a>
+        <img src="data:image/png;base64,AAAA">
+    };
+
+    let expected = "\
+<a href=\"https://example.com/?a=1&amp;b=2\">safe</a>\
+<a href=\"/about\">relative</a>\
+<a href=\"about:invalid#html-node-unsafe-url\" title=\" JavaScript:alert(1)\">evil</a>\
+<img src=\"data:image/png;base64,AAAA\">\
+";
+
+    assert_eq!(html.to_string(), expected);
+}
+
+#[test]
+fn escaping_raw_text_elements() {
+    let evil = "</script><script>alert('evil')</script>";
+
+    let script = Element {
+        name: "script".into(),
+        attributes: Vec::new(),
+        children: Some(vec![text!("if (a < b && c) {{ log({evil:?}); }}")]),
+    };
+    let style = Element {
+        name: "style".into(),
+        attributes: Vec::new(),
+        children: Some(vec![text!("a::after {{ content: '</STYLE>'; }}")]),
+    };
+
+    let html = html! {
+        {script}
+        {style}
+    };
+
+    let expected = r#"<script>if (a < b && c) { log("<\/script><script>alert('evil')<\/script>"); }</script><style>a::after { content: '<\/STYLE>'; }</style>"#;
+
+    assert_eq!(html.to_string(), expected);
+    assert_eq!(
+        format!("{html:#}"),
+        expected.replace("</script><style>", "</script>\n<style>")
+    );
+}
+
+#[test]
+fn escaping_split_raw_text() {
+    use html_node::{el, render::RenderOptions, Text};
+
+    let script = html! {
+        {el::script().child(Text::from("<")).child(Text::from("/script><img src=x onerror=alert(1)>"))}
+    };
+    let style = html! {
+        {el::style().child(Text::from("</st")).child(Text::from("yle><b>"))}
+    };
+
+    let expected = r"<script><\/script><img src=x onerror=alert(1)></script>";
+    assert_eq!(script.to_string(), expected);
+    assert_eq!(script.render(&RenderOptions::minified()).unwrap(), expected);
+    assert_eq!(format!("{script:#}"), expected);
+
+    let expected = r"<style><\/style><b></style>";
+    assert_eq!(style.to_string(), expected);
+    assert_eq!(style.render(&RenderOptions::minified()).unwrap(), expected);
+    assert_eq!(format!("{style:#}"), expected);
+}
+
+#[test]
+fn raw_text() {
+    use html_node::{el, Node, RawText};
+
+    let html = html! {
+        <script>if (a < b) { console.log("<!-- </script>"); }</script>
+    };
+
+    let children = html.as_element().unwrap().children.as_deref().unwrap();
+    assert!(matches!(children, [Node::RawText(_)]));
+
+    assert_eq!(
+        html.to_string(),
+        r#"<script>if (a < b) { console.log("<\!-- <\/script>"); }</script>"#,
+    );
+
+    let standalone = Node::from(RawText::from("a < b </style> <!--"));
+    assert_eq!(standalone.to_string(), r"a < b <\/style> <\!--");
+
+    let mixed = Node::from(
+        el::script()
+            .child(RawText::from("let a = '<!"))
+            .child(text!("-- </scr"))
+            .child(RawText::from("ipt>';")),
+    );
+    assert_eq!(
+        mixed.to_string(),
+        r"<script>let a = '<\!-- <\/script>';</script>",
+    );
+}
+
+#[test]
+fn cdata_and_processing_instructions() {
+    use html_node::{cdata, processing_instruction, render::RenderOptions};
+
+    let html = html! {
+        {processing_instruction!("xml-stylesheet", r#"href="{}""#, "style.css")}
+        <svg>
+            <text>{cdata!("a < b && b > c ]]>")}</text>
+        </svg>
+    };
+
+    assert_eq!(
+        html.render(&RenderOptions::xhtml()).unwrap(),
+        "\
+            <?xml-stylesheet href=\"style.css\"?>\
+            <svg xmlns=\"http://www.w3.org/2000/svg\">\
+                <text><![CDATA[a < b && b ]]>&gt;<![CDATA[ c ]]]]>&gt;<![CDATA[]]></text>\
+            </svg>\
+        ",
+    );
+    assert_eq!(processing_instruction!("php").to_string(), "<?php?>");
+
+    let user = "?><script>alert('evil')</script>";
+    assert_eq!(
+        processing_instruction!("php", "echo '{user}';").to_string(),
+        "<?php echo '?&gt;<script&gt;alert('evil')</script&gt;';?>",
+    );
+
+    let user = "x><script>alert('evil')</script>";
+    assert_eq!(
+        html! { <p>{cdata!("{user}")}</p> }.to_string(),
+        "<p><![CDATA[x]]>&gt;<![CDATA[<script]]>&gt;<![CDATA[alert('evil')</script]]>&gt;<![CDATA[]]></p>",
+    );
+}
+
+#[test]
+fn escaping_comments() {
+    use html_node::{comment, Comment, CommentErrorKind};
+
+    let user = "--><script>alert('evil')</script><!--";
+    let html = html! {
+        <div>{comment!("user: {user}")}</div>
+    };
+
+    assert_eq!(
+        html.to_string(),
+        "<div><!-- user: - -><script>alert('evil')</script><!- - --></div>",
+    );
+
+    let error = |text| Comment::try_new(text).unwrap_err();
+
+    assert_eq!(error(">hi").kind, CommentErrorKind::StartsWithGreaterThan);
+    assert_eq!(error("->hi").kind, CommentErrorKind::StartsWithGreaterThan);
+    assert_eq!(
+        error("a <!-- b").kind,
+        CommentErrorKind::ContainsCommentOpen
+    );
+    assert_eq!(
+        error("a --!> b").kind,
+        CommentErrorKind::ContainsCommentClose
+    );
+    assert_eq!(error("a -- b").kind, CommentErrorKind::ContainsDoubleHyphen);
+    assert_eq!(
+        error(user).to_string(),
+        "comment contains `-->` at offset 0"
+    );
+    assert_eq!(
+        Comment::try_new("a - b > c").unwrap().to_string(),
+        "<!-- a - b > c -->"
+    );
+}
+
+#[test]
+fn doctypes() {
+    use html_node::{
+        render::{RenderError, RenderOptions},
+        Doctype, DoctypeErrorKind, Node,
+    };
+
+    let legacy = html! {
+        <!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN" "http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd">
+    };
+    assert_eq!(legacy, Node::from(Doctype::XHTML1_STRICT));
+    assert_eq!(html! { <!doctype HTML> }, Node::from(Doctype::HTML5));
+
+    assert_eq!(
+        Doctype::parse("html PUBLIC").unwrap_err().kind,
+        DoctypeErrorKind::UnexpectedEof,
+    );
+    assert_eq!(
+        Doctype::parse("html PUBLIC 'a\"><script>'").unwrap_err(),
+        html_node::DoctypeError {
+            kind: DoctypeErrorKind::UnexpectedChar('>'),
+            offset: 15,
+        },
+    );
+    assert_eq!(
+        Doctype::parse("html PRIVATE").unwrap_err().kind,
+        DoctypeErrorKind::UnknownKeyword("PRIVATE".into()),
+    );
+    assert!(Doctype::system("a\"b'c").is_err());
+
+    let options = RenderOptions {
+        normalize_doctypes: true,
+        ..RenderOptions::default()
+    };
+
+    assert_eq!(
+        Node::from(Doctype::from("Html")).render(&options).unwrap(),
+        "<!DOCTYPE html>",
+    );
+    assert_eq!(
+        Node::from(Doctype::HTML4_STRICT).render(&options).unwrap(),
+        r#"<!DOCTYPE html PUBLIC "-//W3C//DTD HTML 4.01//EN" "http://www.w3.org/TR/html4/strict.dtd">"#,
+    );
+
+    let evil = Node::from(Doctype::from("html><script>alert('evil')</script"));
+    assert_eq!(
+        evil.to_string(),
+        "<!DOCTYPE html<scriptalert('evil')</script>",
+    );
+    assert!(matches!(
+        evil.render(&options),
+        Err(RenderError::InvalidDoctype(_))
+    ));
+}
+
+#[test]
+fn shared_nodes() {
+    use std::{sync::Arc, thread};
+
+    use html_node::Node;
+
+    let nav = Arc::new(html! {
+        <nav><a href="/">Home</a><a href="/about">About</a></nav>
+    });
+
+    let pages = ["home", "about"].map(|page| {
+        let nav = Arc::clone(&nav);
+
+        thread::spawn(move || {
+            html! {
+                <body>
+                    {Node::Shared(nav)}
+                    <main>{text!("{page}")}</main>
+                </body>
+            }
+        })
+    });
+
+    let mut pages = pages.map(|page| page.join().unwrap());
+
+    assert_eq!(
+        pages[0].to_string(),
+        r#"<body><nav><a href="/">Home</a><a href="/about">About</a></nav><main>home</main></body>"#,
+    );
+    assert_eq!(pages[0].select("body > nav > a").unwrap().len(), 2);
+    assert_eq!(
+        pages[0]
+            .dfs()
+            .filter(|v| v.node.as_element().is_some())
+            .count(),
+        5
+    );
+
+    pages[1]
+        .select_mut("a[href='/about']", |a| a.add_class("active"))
+        .unwrap();
+
+    assert!(pages[1]
+        .to_string()
+        .contains(r#"<a href="/about" class="active">"#));
+    assert!(!pages[0].to_string().contains("active"));
+    assert!(!nav.to_string().contains("active"));
+}
+
+#[test]
+fn lazy_nodes() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use html_node::{render::RenderOptions, Node};
+
+    let builds = Arc::new(AtomicUsize::new(0));
+
+    let counter = Node::lazy({
+        let builds = Arc::clone(&builds);
+        move || text!("built {} times", builds.fetch_add(1, Ordering::Relaxed) + 1)
+    });
+
+    let html = html! {
+        <ul>
+            {Node::stream(|| (1..=3).map(|i| html! { <li>{text!("{i}")}<br></li> }))}
+        </ul>
+        <p>{counter}</p>
+    };
+
+    assert_eq!(builds.load(Ordering::Relaxed), 0);
+    assert_eq!(
+        html.to_string(),
+        "<ul><li>1<br></li><li>2<br></li><li>3<br></li></ul><p>built 1 times</p>",
+    );
+    assert_eq!(
+        html.render(&RenderOptions::xhtml()).unwrap(),
+        "\
+            <ul xmlns=\"http://www.w3.org/1999/xhtml\"><li>1<br /></li><li>2<br /></li><li>3<br /></li></ul>\
+            <p xmlns=\"http://www.w3.org/1999/xhtml\">built 2 times</p>\
+        ",
+    );
+    assert_eq!(builds.load(Ordering::Relaxed), 2);
+
+    let stream = Node::stream(|| ["a", "b"].map(|text| text!("{text}")));
+    assert_eq!(
+        stream.as_lazy().unwrap().evaluate(),
+        Node::from([text!("a"), text!("b")]),
+    );
+    assert_eq!(stream.clone(), stream);
+    assert_eq!(stream.dfs().count(), 1);
+}
+
+#[test]
+fn pretty_printed_lazy_children() {
+    use std::sync::Arc;
+
+    use html_node::Node;
+
+    let list = Node::lazy(|| html! { <ul><li>a</li></ul> });
+
+    let lazy = html! { <div>{list.clone()}</div> };
+    let shared = html! { <div>{Node::Shared(Arc::new(list))}</div> };
+
+    let expected = "<div>\n    <ul>\n        <li>\n            a\n        </li>\n    </ul>\n</div>";
+
+    assert_eq!(format!("{lazy:#}"), expected);
+    assert_eq!(format!("{shared:#}"), expected);
+}
+
+#[test]
+fn rendering_block_values() {
+    use std::fmt::{self, Write};
+
+    use html_node::{
+        render::{Output, Render},
+        Node,
+    };
+
+    struct Price(u32);
+
+    impl Render for Price {
+        fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+            write!(output, "${}.{:02}", self.0 / 100, self.0 % 100)
+        }
+    }
+
+    let name = String::from("<Ferris>");
+    let nickname: Option<&str> = None;
+    let age: Result<u8, &str> = Ok(7);
+    let scores = [1.5, 2.0];
+
+    let html = html! {
+        <p>{name.as_str()} " (" {nickname} ") is " {age} " years old."</p>
+        <p>{&name}{'!'}</p>
+        <ul>{scores.iter().map(|score| html! { <li>{score}</li> })}</ul>
+    };
+
+    assert_eq!(
+        html.to_string(),
+        "\
+            <p>&lt;Ferris> () is 7 years old.</p>\
+            <p>&lt;Ferris>!</p>\
+            <ul><li>1.5</li><li>2</li></ul>\
+        ",
+    );
+
+    assert_eq!(
+        Ok::<_, &str>(["a", "b"]).into_node(),
+        Node::from([text!("a"), text!("b")]),
+    );
+    assert_eq!(Err::<u8, _>("oops").into_node(), text!("oops"));
+    assert_eq!(Option::<u8>::None.into_node(), Node::EMPTY);
+
+    assert_eq!(Price(1250).into_node(), text!("$12.50"));
+}
+
+#[test]
+fn block_values_into_nodes() {
+    use std::collections::LinkedList;
+
+    use html_node::Node;
+
+    struct Card(&'static str);
+
+    impl From<Card> for Node {
+        fn from(card: Card) -> Self {
+            html! { <div class="card">{text!("{}", card.0)}</div> }
+        }
+    }
+
+    let rows = [["a", "b"], ["c", "d"]];
+    let list = LinkedList::from([text!("x"), text!("y")]);
+
+    let html = html! {
+        <p>{rows.iter().flat_map(|row| row.iter().map(|cell| text!("{cell}")))}</p>
+        <p>{[text!("1"), text!("2")].into_iter().peekable()}</p>
+        <p>{list}</p>
+        {Card("card")}
+        {3}
+    };
+
+    assert_eq!(
+        html.to_string(),
+        r#"<p>abcd</p><p>12</p><p>xy</p><div class="card">card</div>3"#,
+    );
+}
+
+#[test]
+fn optional_attributes() {
+    let title: Option<&str> = None;
+    let tabindex = Some(-1);
+    let (disabled, hidden) = (true, false);
+
+    let html = html! {
+        <button title={title} tabindex={tabindex} disabled={disabled} hidden={hidden} type="submit">
+            Send
+        </button>
+        <input value={String::from("a & b")} checked={Some(false)} required>
+    };
+
+    assert_eq!(
+        html.to_string(),
+        "\
+            <button tabindex=\"-1\" disabled type=\"submit\">Send</button>\
+            <input value=\"a &amp; b\" required>\
+        ",
+    );
+}
+
+#[test]
+fn display_and_enumerated_attributes() {
+    use std::{
+        fmt::{self, Display, Formatter},
+        sync::Arc,
+    };
+
+    struct Id(u32);
+
+    impl Display for Id {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "item-{}", self.0)
+        }
+    }
+
+    let label: Arc<str> = Arc::from("x");
+    let reference = &&"z";
+
+    let html = html! {
+        <div id={Id(3)} data-x={label} data-y={reference} draggable={false} aria-hidden={true} spellcheck={Some(false)} hidden={true}></div>
+    };
+
+    assert_eq!(
+        html.to_string(),
+        "<div id=\"item-3\" data-x=\"x\" data-y=\"z\" draggable=\"false\" aria-hidden=\"true\" spellcheck=\"false\" hidden></div>",
+    );
+}
+
+#[test]
+fn spread_attributes() {
+    let attributes = vec![("data-id", Some("1")), ("hidden", None)];
+    let extra = [(String::from("title"), Some(String::from("\"quoted\"")))];
+
+    let html = html! {
+        <div class="item" {..attributes} {..extra} id="last">
+            <span {..Vec::<(&str, Option<&str>)>::new()}></span>
+        </div>
+    };
+
+    assert_eq!(
+        html.to_string(),
+        "\
+            <div class=\"item\" data-id=\"1\" hidden title=\"&quot;quoted&quot;\" id=\"last\">\
+                <span></span>\
+            </div>\
+        ",
+    );
+
+    let overrides = [("class", Some("primary item")), ("type", Some("submit"))];
+
+    let html = html! {
+        <button class="btn item" type="button" {..overrides}>Go</button>
+    };
+
+    assert_eq!(
+        html.to_string(),
+        "<button class=\"btn item primary\" type=\"submit\">Go</button>",
+    );
+}
+
+#[test]
+fn control_flow() {
+    enum Status {
+        Active { since: u16 },
+        Away,
+    }
+
+    let users = [
+        ("Ferris", Some(Status::Active { since: 2015 })),
+        ("Corro", Some(Status::Away)),
+        ("<anonymous>", None),
+    ];
+    let admin = "Ferris";
+
+    let html = html! {
+        <ul>
+            @for (i, (name, status)) in users.iter().enumerate() {
+                @let id = format!("user-{i}");
+                <li id={id}>
+                    {*name}
+                    @if *name == admin {
+                        " (admin)"
+                    } @else if i == 2 {
+                        " (guest)"
+                    }
+                    @match status {
+                        Some(Status::Active { since }) => { <span>"active since " {*since}</span> }
+                        Some(Status::Away) => { <span>away</span> },
+                        None => {}
+                    }
+                </li>
+            }
+        </ul>
+        @if users.is_empty() {
+            <p>No users.</p>
+        } @else {
+            <p>{users.len()} " users."</p>
+        }
+    };
+
+    assert_eq!(
+        html.to_string(),
+        "\
+            <ul>\
+                <li id=\"user-0\">Ferris (admin)<span>active since 2015</span></li>\
+                <li id=\"user-1\">Corro<span>away</span></li>\
+                <li id=\"user-2\">&lt;anonymous> (guest)</li>\
+            </ul>\
+            <p>3 users.</p>\
+        ",
+    );
+}
+
+#[test]
+fn classes_and_styles() {
+    use html_node::classes;
+
+    let (is_active, hidden) = (true, false);
+    let size: Option<&str> = Some("large");
+    let color = "red; background: url(evil)";
+
+    let html = html! {
+        <button
+            class=["btn", ("active", is_active), ("disabled", !is_active), size, "btn"]
+            style={ {"color": color, "display": (hidden, "none"), "order": 2} }
+        >
+            Go
+        </button>
+        <div class=[("hidden", hidden)] style={ {"display": (hidden, "none")} }></div>
+        <div class={classes!["a b", "b c"]}></div>
+    };
+
+    assert_eq!(
+        html.to_string(),
+        "\
+            <button class=\"btn active large\" style=\"color: red\\3B  background: url(evil); order: 2\">Go</button>\
+            <div></div>\
+            <div class=\"a b c\"></div>\
+        ",
+    );
+
+    let comment = "red /*";
+    let quote = "'a";
+    let display: std::sync::Arc<str> = "none".into();
+
+    let html = html! {
+        <p style={ {"color": comment, "font-family": quote, "font": "12px/1.5 serif", "display": display} }></p>
+    };
+
+    assert_eq!(
+        html.to_string(),
+        "<p style=\"color: red \\2F *; font-family: \\27 a; font: 12px/1.5 serif; display: none\"></p>",
+    );
+}
+
+#[test]
+fn visit_mut_rewrites_links() {
+    use html_node::visit::{self, VisitMut};
+
+    struct Rewrite;
+
+    impl VisitMut for Rewrite {
+        fn visit_element_mut(&mut self, element: &mut Element) {
+            for (key, value) in &mut element.attributes {
+                if let ("href", Some(value)) = (key.as_str(), value) {
+                    *value = format!("https://example.com{value}");
+                }
+            }
+
+            visit::visit_element_mut(self, element);
+        }
+    }
+
+    let mut html = html! {
+        <nav>
+            <a href="/">home</a>
+            <>
+                <a href="/about">about</a>
+            </>
+        </nav>
+    };
+
+    Rewrite.visit_node_mut(&mut html);
+
+    assert_eq!(
+        html.to_string(),
+        "<nav>\
+            <a href=\"https://example.com/\">home</a>\
+            <a href=\"https://example.com/about\">about</a>\
+        </nav>",
+    );
+}
+
+#[test]
+fn traversal_parents() {
+    let html = html! {
+        <div>
+            <p>one</p>
+            <ul>
+                <li>two</li>
+            </ul>
+        </div>
+    };
+
+    let name = |node: &html_node::Node| {
+        node.as_element()
+            .map_or_else(|| node.to_string(), |element| element.name.clone())
+    };
+
+    let dfs = html
+        .dfs()
+        .map(|visited| {
+            let parents = visited.parents.iter().map(|node| name(node));
+            (name(visited.node), parents.collect::<Vec<_>>())
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        dfs,
+        [
+            ("div".into(), vec![]),
+            ("p".into(), vec!["div".to_owned()]),
+            ("one".into(), vec!["div".into(), "p".into()]),
+            ("ul".into(), vec!["div".into()]),
+            ("li".into(), vec!["div".into(), "ul".into()]),
+            ("two".into(), vec!["div".into(), "ul".into(), "li".into()]),
+        ],
+    );
+
+    let bfs = html
+        .bfs()
+        .map(|visited| (name(visited.node), visited.depth()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        bfs,
+        [
+            ("div".into(), 0),
+            ("p".into(), 1),
+            ("ul".into(), 1),
+            ("one".into(), 2),
+            ("li".into(), 2),
+            ("two".into(), 3),
+        ],
+    );
+}
+
+#[test]
+fn element_attributes() {
+    let mut element = Element::new("button");
+
+    element.set_attr("type", "submit");
+    element.set_bool_attr("disabled", true);
+    element.add_class("btn btn-primary");
+    element.toggle_class("active");
+    element.merge_style("color: red; background: url(\"a;b.png\")");
+    element.set_style("COLOR", "blue");
+
+    assert_eq!(element.get_attr("type"), Some("submit"));
+    assert_eq!(element.get_attr("disabled"), Some(""));
+    assert!(element.has_class("active"));
+    assert_eq!(element.get_style("color"), Some("blue"));
+    assert_eq!(
+        element.to_string(),
+        "<button type=\"submit\" disabled class=\"btn btn-primary active\" \
+        style=\"color: blue; background: url(&quot;a;b.png&quot;)\"></button>",
+    );
+
+    assert_eq!(element.remove_attr("TYPE"), Some("submit".into()));
+    assert!(!element.has_attr("type"));
+    element.set_bool_attr("disabled", false);
+    assert!(!element.toggle_class("active"));
+    element.remove_class("btn btn-primary");
+    assert_eq!(
+        element.remove_style("background"),
+        Some("url(\"a;b.png\")".into())
+    );
+
+    assert_eq!(
+        element.to_string(),
+        "<button style=\"color: blue\"></button>"
+    );
+}
+
+#[test]
+fn duplicate_attributes() {
+    use html_node::render::{DuplicateAttributes, RenderError, RenderOptions};
+
+    let html = html! {
+        <div class="a" id="x" class="b a" id="y"></div>
+    };
+
+    let render = |duplicate_attributes| {
+        html.render(&RenderOptions {
+            duplicate_attributes,
+            ..RenderOptions::default()
+        })
+    };
+
+    assert_eq!(
+        render(DuplicateAttributes::KeepAll).unwrap(),
+        html.to_string(),
+    );
+    assert_eq!(
+        render(DuplicateAttributes::KeepFirst).unwrap(),
+        r#"<div class="a" id="x"></div>"#,
+    );
+    assert_eq!(
+        render(DuplicateAttributes::KeepLast).unwrap(),
+        r#"<div class="b a" id="y"></div>"#,
+    );
+    assert_eq!(
+        render(DuplicateAttributes::MergeClass).unwrap(),
+        r#"<div class="a b" id="x"></div>"#,
+    );
+    assert!(matches!(
+        render(DuplicateAttributes::Error),
+        Err(RenderError::DuplicateAttribute { element, attribute })
+            if element == "div" && attribute == "class"
+    ));
+
+    let mut bytes = Vec::new();
+    html.render_to(
+        &mut bytes,
+        &RenderOptions {
+            duplicate_attributes: DuplicateAttributes::KeepFirst,
+            ..RenderOptions::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(bytes, br#"<div class="a" id="x"></div>"#);
+}
+
+#[test]
+fn xhtml() {
+    use html_node::render::RenderOptions;
+
+    let html = html! {
+        <html>
+            <body>
+                <input type="checkbox" checked title="it's \"quoted\"">
+                <br>
+                <script>"if (a < b && c) {}"</script>
+                <svg viewBox="0 0 10 10" class="icon">
+                    <use xlink:href="#dot"></use>
+                    <foreignObject>
+                        <p>"1 < 2"</p>
+                    </foreignObject>
+                </svg>
+                <math><mi>x</mi></math>
+            </body>
+        </html>
+    };
+
+    let expected = "\
+        <html xmlns=\"http://www.w3.org/1999/xhtml\">\
+            <body>\
+                <input type=\"checkbox\" checked=\"checked\" title=\"it&apos;s &quot;quoted&quot;\" />\
+                <br />\
+                <script>\"if (a &lt; b &amp;&amp; c) {}\"</script>\
+                <svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 10 10\" class=\"icon\">\
+                    <use xmlns:xlink=\"http://www.w3.org/1999/xlink\" xlink:href=\"#dot\"></use>\
+                    <foreignObject>\
+                        <p xmlns=\"http://www.w3.org/1999/xhtml\">1 &lt; 2</p>\
+                    </foreignObject>\
+                </svg>\
+                <math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mi>x</mi></math>\
+            </body>\
+        </html>\
+    ";
+
+    assert_eq!(html.render(&RenderOptions::xhtml()).unwrap(), expected);
+
+    let declared = html! {
+        <svg xmlns="http://www.w3.org/2000/svg" class="a" class="b"><circle r="1" /></svg>
+    };
+
+    assert_eq!(
+        declared.render(&RenderOptions::xhtml()).unwrap(),
+        r#"<svg xmlns="http://www.w3.org/2000/svg" class="a"><circle r="1"></circle></svg>"#,
+    );
+}
+
+#[test]
+fn minify() {
+    use html_node::render::RenderOptions;
+
+    let html = html! {
+        <!DOCTYPE html>
+        <html>
+            <head>
+                <title>"  Shopping   List "</title>
+            </head>
+            <body>
+                <!-- "navigation" -->
+                <a href="/home" class="nav link" title="">"Home"</a>
+                <p>"first"</p>
+                <p>"second"</p>
+                <pre>"  keep\n   this  "</pre>
+                <table>
+                    <tr><td>"a"</td><td>"b"</td></tr>
+                </table>
+                <script>"let  x =  1;"</script>
+            </body>
+        </html>
+    };
+
+    assert_eq!(
+        html.render(&RenderOptions::minified()).unwrap(),
+        "\
+            <!DOCTYPE html>\
+            <html>\
+            <head>\
+            <title> Shopping List </title>\
+            <body>\
+            <a href=/home class=\"nav link\" title>Home</a>\
+            <p>first\
+            <p>second\
+            <pre>  keep\n   this  </pre>\
+            <table><tr><td>a<td>b</table>\
+            <script>\"let  x =  1;\"</script>\
+        ",
+    );
+
+    let custom = html! {
+        <div><my-el><p>"x"</p></my-el><p>"y"</p></div>
+    };
+
+    assert_eq!(
+        custom.render(&RenderOptions::minified()).unwrap(),
+        "<div><my-el><p>x</p></my-el><p>y</div>",
+    );
+}
+
+#[test]
+fn minify_adjacent_text() {
+    use std::fmt::{self, Write};
+
+    use html_node::render::{Output, Render, RenderOptions};
+
+    struct Padded(&'static str);
+
+    impl Render for Padded {
+        fn render_html(&self, output: &mut Output<'_>) -> fmt::Result {
+            write!(output, "  {}  ", self.0)
+        }
+    }
+
+    let html = html! {
+        <p>"a " " b" {Padded("c")} "\n d"<br>" e"</p>
+    };
+
+    assert_eq!(
+        html.render(&RenderOptions::minified()).unwrap(),
+        "<p>a b c d<br> e</p>",
+    );
+}
+
+#[test]
+fn element_builder() {
+    use html_node::el;
+
+    let items = ["milk", "eggs"];
+
+    let built = el::div()
+        .attr("id", "list")
+        .class("card")
+        .class("wide")
+        .style("color", "red")
+        .child(el::h1().child(text!("Shopping List")))
+        .child(
+            el::input()
+                .attr("type", "checkbox")
+                .bool_attr("checked", true),
+        )
+        .children(items.map(|item| el::li().child(text!("{item}"))));
+
+    let expected = html! {
+        <div id="list" class="card wide" style="color: red">
+            <h1>Shopping List</h1>
+            <input type="checkbox" checked>
+            <li>milk</li>
+            <li>eggs</li>
+        </div>
+    };
+
+    assert_eq!(html_node::Node::from(built), expected);
+}
+
+#[test]
+fn void_elements() {
+    let br = Element {
+        name: "br".into(),
+        attributes: Vec::new(),
+        children: Some(vec![text!("ignored")]),
+    };
+    assert_eq!(br.to_string(), "<br>");
+
+    let div = Element::from("div");
+    assert_eq!(div.to_string(), "<div></div>");
+    assert_eq!(format!("{div:#}"), "<div></div>");
+
+    let empty = Element::new("div");
+    assert_eq!(empty.to_string(), "<div></div>");
+    assert_eq!(format!("{empty:#}"), "<div>\n</div>");
+
+    assert_eq!(Element::new("IMG").children, None);
+    assert_eq!(html! { <img> }, Element::new("img").into());
+    assert!(html_node::is_void_element("Input"));
+    assert!(html_node::is_raw_text_element("script"));
+}
@@ -0,0 +1,121 @@
+#![allow(missing_docs)]
+#![cfg(feature = "parse")]
+
+use html_node::{
+    html,
+    parse::{ParseError, ParseErrorKind},
+    text, Node,
+};
+
+#[test]
+fn parse_matches_macro() {
+    let parsed = Node::parse(
+        r#"<div class="item"><input type=checkbox id='item-1' checked><label for="item-1">milk &amp; eggs</label></div>"#,
+    )
+    .unwrap();
+
+    let expected = html! {
+        <div class="item">
+            <input type="checkbox" id="item-1" checked>
+            <label for="item-1">{text!("milk & eggs")}</label>
+        </div>
+    };
+
+    assert_eq!(parsed, expected);
+}
+
+#[test]
+fn parse_round_trip() {
+    let html = "<!DOCTYPE html><html><head><title>a &lt; b</title></head><body><!-- comment \
+                --><p>Hello, <strong>world</strong>!</p><br><img src=\"/a.png\" alt=\"\"></body></html>";
+
+    let parsed: Node = html.parse().unwrap();
+
+    assert_eq!(parsed.to_string(), html);
+}
+
+#[test]
+fn parse_raw_text() {
+    let parsed = Node::parse("<script>if (a < b) { document.write('</div>') }</script>").unwrap();
+
+    assert_eq!(
+        parsed.as_element().unwrap().children.as_deref().unwrap()[0]
+            .as_raw_text()
+            .unwrap()
+            .text,
+        "if (a < b) { document.write('</div>') }",
+    );
+}
+
+#[test]
+fn parse_cdata_and_processing_instructions() {
+    let html = r#"<?xml version="1.0"?><svg><![CDATA[a < b]]></svg>"#;
+    let parsed = Node::parse(html).unwrap();
+
+    let children = parsed.as_fragment().unwrap().children.as_slice();
+    let instruction = children[0].as_processing_instruction().unwrap();
+    assert_eq!(instruction.target, "xml");
+    assert_eq!(instruction.data, r#"version="1.0""#);
+
+    let svg = children[1].as_element().unwrap();
+    assert_eq!(
+        svg.children.as_deref().unwrap()[0].as_cdata().unwrap().text,
+        "a < b"
+    );
+
+    assert_eq!(parsed.to_string(), html);
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        Node::parse("<div>\n  <p>text</div>"),
+        Err(ParseError {
+            kind: ParseErrorKind::UnexpectedClosingTag {
+                expected: Some("p".into()),
+                found: "div".into(),
+            },
+            offset: 15,
+            line: 2,
+            column: 10,
+        }),
+    );
+
+    assert_eq!(
+        Node::parse("<ul>\n<li>").unwrap_err().kind,
+        ParseErrorKind::UnclosedElement("li".into()),
+    );
+
+    assert_eq!(
+        Node::parse("<br></br>").unwrap_err().kind,
+        ParseErrorKind::VoidClosingTag("br".into()),
+    );
+
+    assert_eq!(
+        Node::parse("<div class=\"a>").unwrap_err().to_string(),
+        "unexpected end of input at 1:12",
+    );
+}
+
+#[test]
+fn parse_lenient() {
+    use html_node::parse::parse_lenient;
+
+    assert_eq!(
+        parse_lenient("<p>a <b>b</p><p>c</i>").to_string(),
+        "<p>a <b>b</b></p><p>c</p>",
+    );
+    assert_eq!(parse_lenient("<p><!-- a").to_string(), "<p><!-- a --></p>");
+    assert_eq!(
+        parse_lenient("<!ELEMENT p><p>a</p>").to_string(),
+        "<!-- ELEMENT p --><p>a</p>",
+    );
+    assert_eq!(
+        parse_lenient("<style>a { b: c }").to_string(),
+        "<style>a { b: c }</style>",
+    );
+    assert_eq!(
+        parse_lenient("<div class=\"a>b").to_string(),
+        "&lt;div class=\"a>b",
+    );
+}
@@ -0,0 +1,90 @@
+#![allow(missing_docs)]
+#![cfg(feature = "sanitize")]
+
+use html_node::{html, sanitize::Policy, unsafe_text};
+
+#[test]
+fn sanitize_basic() {
+    let sanitized = Policy::basic()
+        .sanitize_html(
+            r#"<div class="post"><p onmouseover="steal()">Hello, <b>world</b>!</p><img src="x" onerror="steal()"><style>body { display: none }</style></div>"#,
+        );
+
+    assert_eq!(sanitized.to_string(), "<p>Hello, <b>world</b>!</p>");
+}
+
+#[test]
+fn sanitize_urls() {
+    let sanitized = Policy::basic().sanitize_html(
+        "<a href=\"https://example.com\">ok</a>\
+             <a href=\"/relative\">ok</a>\
+             <a href=\"java&#x09;script:alert(1)\">bad</a>\
+             <a href=\" JavaScript:alert(1)\">bad</a>\
+             <a href=\"data:text/html,hi\">bad</a>",
+    );
+
+    assert_eq!(
+        sanitized.to_string(),
+        "<a href=\"https://example.com\">ok</a>\
+         <a href=\"/relative\">ok</a>\
+         <a>bad</a>\
+         <a>bad</a>\
+         <a>bad</a>",
+    );
+}
+
+#[test]
+fn sanitize_node_tree() {
+    let untrusted = html! {
+        <div>
+            <h1 id="title">Title</h1>
+            {unsafe_text!("<script>steal()</script><em>emphasis</em>")}
+            <!-- "a comment" -->
+        </div>
+    };
+
+    assert_eq!(
+        untrusted.clone().sanitize(&Policy::rich_text()).to_string(),
+        "<div><h1>Title</h1><em>emphasis</em></div>",
+    );
+
+    assert_eq!(
+        untrusted.sanitize(&Policy::text_only()).to_string(),
+        "Titleemphasis",
+    );
+}
+
+#[test]
+fn sanitize_badly_nested_html() {
+    let sanitized =
+        Policy::basic().sanitize_html("<p>Hello, <b><i>world</b></i>!</p><p>Unclosed <em>text");
+
+    assert_eq!(
+        sanitized.to_string(),
+        "<p>Hello, <b><i>world</i></b>!</p><p>Unclosed <em>text</em></p>",
+    );
+}
+
+#[test]
+fn sanitize_malformed_html() {
+    let policy = Policy::basic();
+
+    assert_eq!(
+        policy
+            .sanitize_html("<p>a</p><!-- unterminated <b>")
+            .to_string(),
+        "<p>a</p>",
+    );
+    assert_eq!(
+        policy
+            .sanitize_html("<p>a</p><!ENTITY x><b>b</b><script>alert(1)")
+            .to_string(),
+        "<p>a</p><b>b</b>",
+    );
+    assert_eq!(
+        policy
+            .sanitize_html("<p>a <img src=x onerror=\"alert(1)")
+            .to_string(),
+        "<p>a &lt;img src=x onerror=\"alert(1)</p>",
+    );
+}
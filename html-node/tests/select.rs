@@ -0,0 +1,131 @@
+#![allow(missing_docs)]
+
+use html_node::{html, select::SelectorErrorKind, Node};
+
+fn page() -> Node {
+    html! {
+        <main>
+            <form id="login" class="form narrow">
+                <input name="user">
+                <input name="password" type="password">
+            </form>
+            <ul>
+                <li><a href="/">home</a></li>
+                <li><a href="https://example.com" class="external">example</a></li>
+                <>
+                    <li><a href="HTTP://EXAMPLE.ORG">shouting</a></li>
+                </>
+            </ul>
+        </main>
+    }
+}
+
+fn select(node: &Node, selector: &str) -> Vec<String> {
+    node.select(selector)
+        .unwrap()
+        .into_iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn simple_selectors() {
+    let page = page();
+
+    assert_eq!(select(&page, "#login").len(), 1);
+    assert_eq!(select(&page, "form.narrow#login").len(), 1);
+    assert_eq!(select(&page, "FORM.form").len(), 1);
+    assert_eq!(select(&page, ".wide").len(), 0);
+    assert_eq!(select(&page, "*").len(), 11);
+    assert_eq!(select(&page, "a, input").len(), 5);
+}
+
+#[test]
+fn attribute_selectors() {
+    let page = page();
+
+    assert_eq!(
+        select(&page, "input[type=password]"),
+        [r#"<input name="password" type="password">"#],
+    );
+    assert_eq!(
+        select(&page, "input:not([type])"),
+        [r#"<input name="user">"#]
+    );
+    assert_eq!(select(&page, "a[href^=http]").len(), 1);
+    assert_eq!(select(&page, "a[href^=http i]").len(), 2);
+    assert_eq!(select(&page, "a[href$='.com']").len(), 1);
+    assert_eq!(select(&page, "a[href*=example i]").len(), 2);
+    assert_eq!(select(&page, "[class~=narrow]").len(), 1);
+    assert_eq!(select(&page, "[id|=login]").len(), 1);
+}
+
+#[test]
+fn combinators_and_pseudo_classes() {
+    let page = page();
+
+    assert_eq!(select(&page, "main a").len(), 3);
+    assert_eq!(select(&page, "main > a").len(), 0);
+    assert_eq!(select(&page, "ul > li > a").len(), 3);
+    assert_eq!(
+        select(&page, "li:first-child a"),
+        [r#"<a href="/">home</a>"#]
+    );
+    assert_eq!(
+        select(&page, "li:last-child > a"),
+        [r#"<a href="HTTP://EXAMPLE.ORG">shouting</a>"#],
+    );
+    assert_eq!(
+        select(&page, "li:not(:first-child, :last-child) a").len(),
+        1
+    );
+}
+
+#[test]
+fn select_first() {
+    let page = page();
+
+    let form = page.select_first("form").unwrap().unwrap();
+    assert_eq!(form.name, "form");
+
+    assert!(page.select_first("table").unwrap().is_none());
+}
+
+#[test]
+fn select_mut() {
+    let mut page = page();
+
+    page.select_mut("a[href^=http i]", |a| {
+        a.attributes.push(("rel".into(), Some("noopener".into())));
+    })
+    .unwrap();
+
+    assert_eq!(select(&page, "a[rel=noopener]").len(), 2);
+
+    let input = page.select_first_mut("#login input").unwrap().unwrap();
+    input.set_attr("value", "hi");
+
+    assert_eq!(
+        select(&page, "input")[0],
+        r#"<input name="user" value="hi">"#
+    );
+}
+
+#[test]
+fn invalid_selectors() {
+    let page = page();
+
+    let error = page.select("a > ").unwrap_err();
+    assert_eq!(error.kind, SelectorErrorKind::UnexpectedEof);
+    assert_eq!(error.offset, 4);
+
+    let error = page.select("a:hover").unwrap_err();
+    assert_eq!(
+        error.kind,
+        SelectorErrorKind::UnsupportedPseudoClass("hover".into()),
+    );
+    assert_eq!(error.offset, 2);
+
+    let error = page.select("a]").unwrap_err();
+    assert_eq!(error.kind, SelectorErrorKind::UnexpectedChar(']'));
+}
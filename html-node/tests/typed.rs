@@ -1,6 +1,10 @@
+#![allow(missing_docs)]
+#![cfg(feature = "typed")]
+
 use html_node::{
-    text,
+    component, text,
     typed::{self, elements::*, html},
+    Node,
 };
 
 #[test]
@@ -147,3 +151,91 @@ fn component() {
 
     assert_eq!(html.to_string(), expected);
 }
+
+#[test]
+fn spread_attributes() {
+    typed::component! {
+        Button {
+            label: &'static str,
+        };
+
+        |ButtonAttributes { label }, extra_attributes, _| html! {
+            <button type="button" class="btn" data-action="none" {..extra_attributes}>
+                {text!("{label}")}
+            </button>
+        }
+    }
+
+    let overrides = [("class", Some("primary")), ("type", Some("submit"))];
+
+    let html = html! {
+        <div id="actions" {..[("role", Some("toolbar"))]}>
+            <Button component label="Save" data-action="save" aria-pressed="false" {..overrides}></Button>
+        </div>
+    };
+
+    let expected = "\
+<div id=\"actions\" role=\"toolbar\">\
+    <button class=\"btn primary\" type=\"submit\" data-action=\"save\" aria-pressed=\"false\">Save</button>\
+</div>\
+";
+
+    assert_eq!(html.to_string(), expected);
+}
+
+#[test]
+fn classes_and_styles() {
+    let is_active = true;
+
+    let html = html! {
+        <div class=["card", ("active", is_active)] style={ {"width": 100, "height": None::<i32>} }></div>
+    };
+
+    assert_eq!(
+        html.to_string(),
+        "<div class=\"card active\" style=\"width: 100\"></div>",
+    );
+}
+
+#[test]
+fn function_component() {
+    #[component]
+    fn Card(
+        title: String,
+        subtitle: Option<String>,
+        #[default] level: u8,
+        #[default = "card".to_owned()] kind: String,
+        highlighted: bool,
+        extra_attributes: Vec<(String, Option<String>)>,
+        children: Node,
+    ) -> Node {
+        html! {
+            <section class=[kind, ("highlighted", highlighted)] {..extra_attributes}>
+                <h2>{title}</h2>
+                {subtitle}
+                {level}
+                {children}
+            </section>
+        }
+    }
+
+    let html = html! {
+        <div>
+            <Card title="Milk"></Card>
+            <Card title="Eggs" subtitle="A dozen" level=3 kind="item" highlighted data-id="2">
+                <p>"Free range"</p>
+            </Card>
+        </div>
+    };
+
+    let expected = "\
+<div>\
+    <section class=\"card\"><h2>Milk</h2>0</section>\
+    <section class=\"item highlighted\" data-id=\"2\">\
+        <h2>Eggs</h2>A dozen3<p>Free range</p>\
+    </section>\
+</div>\
+";
+
+    assert_eq!(html.to_string(), expected);
+}